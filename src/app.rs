@@ -6,16 +6,40 @@ use ratatui::{
     style::{Color, Style, Stylize},
     text::Line,
     widgets::{
-        Bar, BarChart, BarGroup, Block, Borders, List, ListItem, ListState, Paragraph,
+        Axis, Bar, BarChart, BarGroup, Block, Borders, Cell, Chart, Dataset, Gauge, GraphType,
+        LineGauge, List, ListItem, ListState, Paragraph, Row, Table, TableState, Tabs,
         calendar::{self},
     },
 };
-use time::OffsetDateTime;
+use std::collections::HashMap;
+use std::time::Instant;
+use time::{Date, OffsetDateTime};
 
-use crate::user_habits;
+use crate::calendar_view::CalendarView;
+use crate::challenge::Outcome;
+use crate::checklist::{self, ChecklistItem};
+use crate::dashboard::{self, DashboardHabit, TodayStatus};
+use crate::habit_table::{self, HabitColumn};
+use crate::heatmap::YearHeatmap;
+use crate::history;
+use crate::model::Model;
+use crate::search;
+use crate::sort_mode::SortMode;
+use crate::stats::{self, HabitStats, QuitStats};
+use crate::stats_view::{ChartValue, StatsView};
+use crate::streak;
+use crate::timer::Timer;
+use crate::trend::{self, Trend};
+use crate::user_habits::{self, Aggregate};
 use crate::{date_styler::CompletedDateStyler, my_colors::SELECTED_STYLE};
-use crate::{db::db, text_input::TextInput};
-use crate::{input_mode::InputMode, my_colors};
+use crate::{
+    db::{TimeFrame, db},
+    text_input::TextInput,
+};
+use crate::{
+    input_mode::{HabitField, InputMode},
+    my_colors,
+};
 use color_eyre::Result;
 // /// The main application which holds the state and logic of the application.
 #[derive(Debug, Default)]
@@ -28,6 +52,45 @@ pub struct App {
     pub habit_hours_buffer: TextInput,
     pub habit_hours_done: bool,
     pub habit_name_buffer: TextInput,
+    /// Shared buffer for [`InputMode::EditingHabit`].
+    pub habit_edit_buffer: TextInput,
+    /// Short feedback line shown under the habit list (e.g. export results).
+    pub status: String,
+    /// How many months back from the current one the calendar is showing.
+    pub calendar_month_offset: i32,
+    /// Year shown by the heatmap view.
+    pub heatmap_year: i32,
+    /// Shade heatmap cells by logged hours instead of completion count.
+    pub heatmap_by_hours: bool,
+    /// Whether the stats chart groups by week or by month.
+    pub stats_timeframe: TimeFrame,
+    /// How many periods the stats chart is scrolled back from the current one.
+    pub stats_offset: u32,
+    /// What the stats charts plot: hours, completions or one of the habit's metrics.
+    pub stats_value: ChartValue,
+    /// What the log box records: 0 for hours, otherwise the habit's metric at `log_metric - 1`.
+    pub log_metric: usize,
+    /// Plot the trend chart per week instead of per day.
+    pub trend_weekly: bool,
+    /// Tag or category the habit list is filtered to.
+    pub tag_filter: Option<String>,
+    pub search_buffer: TextInput,
+    /// Notes matching the search box, refreshed as it is typed in.
+    pub search_hits: Vec<search::NoteMatch>,
+    /// Note match highlighted in the search results, if any.
+    pub search_selected: Option<usize>,
+    /// Habits and their data as last read from the database.
+    pub model: Model,
+    /// Habit whose rows the current event changed, when no others were.
+    pub touched: Option<u64>,
+    /// Date of the last tick, to notice when midnight passes.
+    pub today: Option<Date>,
+    /// Stopwatch or pomodoro running on a habit.
+    pub timer: Option<Timer>,
+    /// Selected row of the today checklist.
+    pub checklist_state: ListState,
+    /// Ids of the habits picked in [`InputMode::Selecting`].
+    pub picked: Vec<u64>,
     pub db: db,
 }
 
@@ -40,18 +103,31 @@ impl App {
     /// Run the application's main loop.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         self.running = true;
-        self.input_mode = InputMode::Normal;
+        // the day starts on the today checklist
+        self.input_mode = InputMode::Checklist;
+        self.checklist_state.select_first();
         self.habits = user_habits::UserHabits {
             show_habit_list: true,
             habit_calendar_track: true,
             show_add_habit: false,
+            show_dashboard: false,
+            show_archived: false,
+            sort_mode: SortMode::Manual,
+            hidden_columns: self.load_hidden_columns(),
             habit_stats: true,
+            calendar_view: CalendarView::Month,
+            stats_view: StatsView::History,
             items: vec![],
             state: ListState::default(),
         };
+        self.heatmap_year = OffsetDateTime::now_utc().year();
+        self.heatmap_by_hours = true;
+        self.close_finished_challenges();
+        self.reload();
         while self.running {
             terminal.draw(|frame| self.render(frame))?;
             self.handle_crossterm_events()?;
+            self.on_tick();
         }
         Ok(())
     }
@@ -63,14 +139,27 @@ impl App {
     /// - <https://docs.rs/ratatui/latest/ratatui/widgets/index.html>
     /// - <https://github.com/ratatui/ratatui/tree/main/ratatui-widgets/examples>
     fn render(&mut self, frame: &mut Frame) {
+        if self.input_mode == InputMode::Checklist {
+            self.load_habits();
+            self.render_checklist(frame, frame.area());
+            return;
+        }
         // let x: u16 = ((frame.count() as f64).sin() * 10.0 + 20.0).floor() as u16;
         let outer_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
             .split(frame.area());
+        // month grids need more room than a single month, so they take most of the column
+        let calendar_height = match self.habits.calendar_view {
+            CalendarView::MonthGrid(_) => 75,
+            _ => 40,
+        };
         let inner_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
+            .constraints(vec![
+                Constraint::Percentage(calendar_height),
+                Constraint::Percentage(100 - calendar_height),
+            ])
             .split(outer_layout[1]);
         let enter_hours_layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -82,12 +171,28 @@ impl App {
             .split(outer_layout[0]);
         if self.habits.show_habit_list {
             // self.habit_list_block(outer_layout[0], frame.buffer_mut());
-            self.habits.items = self.db.get_habits().clone();
-            let items = self.habits.items.clone();
-            let (_items, list_widget) = Self::habit_list_block(&items, &self.input_mode);
-            frame.render_stateful_widget(list_widget, outer_layout[0], &mut self.habits.state);
+            let labels = self.load_habits();
+            let list_area = if labels.is_empty() {
+                outer_layout[0]
+            } else {
+                let areas = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(3), Constraint::Min(0)])
+                    .split(outer_layout[0]);
+                frame.render_widget(self.filter_bar(&labels), areas[0]);
+                areas[1]
+            };
+            let (rows, table) = self.habit_list_block();
+            // the table has header rows, so the selected habit is mapped to its row
+            let mut table_state = TableState::default()
+                .with_offset(self.habits.state.offset())
+                .with_selected(self.habits.state.selected().map(|idx| rows[idx]));
+            frame.render_stateful_widget(table, list_area, &mut table_state);
+            *self.habits.state.offset_mut() = table_state.offset();
         }
-        if self.habits.habit_calendar_track {
+        if self.habits.show_dashboard {
+            self.render_dashboard(frame, outer_layout[1]);
+        } else if self.habits.habit_calendar_track {
             // frame.render_widget(self.habits.habit_calendar_tracker_block(), inner_layout[0]);
             let mut border_style = my_colors::NORMAL_STYLE;
             if self.input_mode == InputMode::MarkingDone {
@@ -103,34 +208,277 @@ impl App {
                 .borders(Borders::ALL)
                 .border_style(border_style);
 
-            let block = self.habit_calendar_tracker_block(&habit_calendar_tracker_title_block);
-            if block.is_some() {
-                frame.render_widget(&block, enter_hours_layout[0]);
-            } else {
-                frame.render_widget(&habit_calendar_tracker_title_block, enter_hours_layout[0]);
+            match self.habits.calendar_view {
+                CalendarView::Month => {
+                    let block =
+                        self.habit_calendar_tracker_block(&habit_calendar_tracker_title_block);
+                    if block.is_some() {
+                        frame.render_widget(&block, enter_hours_layout[0]);
+                    } else {
+                        frame.render_widget(
+                            &habit_calendar_tracker_title_block,
+                            enter_hours_layout[0],
+                        );
+                    }
+                }
+                CalendarView::MonthGrid(months) => self.render_month_grid(
+                    frame,
+                    enter_hours_layout[0],
+                    habit_calendar_tracker_title_block,
+                    months,
+                ),
+                CalendarView::YearHeatmap => {
+                    match self.habit_heatmap_block(habit_calendar_tracker_title_block.clone()) {
+                        Some(heatmap) => frame.render_widget(heatmap, enter_hours_layout[0]),
+                        None => frame.render_widget(
+                            &habit_calendar_tracker_title_block,
+                            enter_hours_layout[0],
+                        ),
+                    }
+                }
             }
             // Render hours input block
             self.render_hours_input(frame, enter_hours_layout[1]);
         }
-        if self.habits.habit_stats {
-            let block = self.habit_stats_tracker();
-            if block.is_some() {
-                frame.render_widget(&block, inner_layout[1])
+        if self.habits.habit_stats && !self.habits.show_dashboard {
+            match self.habits.stats_view {
+                StatsView::History => {
+                    let challenge = self.render_challenge_gauge(frame, inner_layout[1]);
+                    let below_challenge = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(challenge), Constraint::Min(0)])
+                        .split(inner_layout[1])[1];
+                    let targets = self.render_target_gauges(frame, below_challenge);
+                    let chart_area = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(targets), Constraint::Min(0)])
+                        .split(below_challenge)[1];
+                    let block = self.habit_stats_tracker(chart_area.width);
+                    if block.is_some() {
+                        frame.render_widget(&block, chart_area)
+                    }
+                }
+                StatsView::Trend => self.render_trend_chart(frame, inner_layout[1]),
+                StatsView::Summary => self.render_habit_summary(frame, inner_layout[1]),
+                StatsView::Tags => self.render_tag_stats(frame, inner_layout[1]),
             }
         }
         if self.habits.show_add_habit {
             self.display_add_habit(frame, left_layout[1]);
         }
+        if let InputMode::EditingHabit(field) = self.input_mode {
+            self.display_edit_habit(frame, left_layout[1], field);
+        }
+        if self.input_mode == InputMode::Searching {
+            self.display_search(frame, left_layout[1]);
+        }
+    }
+
+    /// Rebuilds the habit list from the model through the archive and tag filters,
+    /// keeping the selection in range. Returns the tags and categories in use.
+    pub fn load_habits(&mut self) -> Vec<String> {
+        let labels = self.model.labels.clone();
+        if self
+            .tag_filter
+            .as_ref()
+            .is_some_and(|label| !labels.contains(label))
+        {
+            self.tag_filter = None;
+        }
+        let show_archived = self.habits.show_archived;
+        let tag_filter = self.tag_filter.clone();
+        let mut items: Vec<user_habits::HabitItem> = self
+            .model
+            .habits
+            .iter()
+            .filter(|habit| habit.active || show_archived)
+            .filter(|habit| {
+                tag_filter
+                    .as_ref()
+                    .is_none_or(|label| habit.has_label(label))
+            })
+            .cloned()
+            .collect();
+        match self.habits.sort_mode {
+            // get_habits already returns the saved order
+            SortMode::Manual => {}
+            SortMode::Name => items.sort_by_key(|habit| habit.name.to_lowercase()),
+            SortMode::Streak => items.sort_by_key(|habit| std::cmp::Reverse(habit.current_streak)),
+            SortMode::WeekHours => {
+                let today = OffsetDateTime::now_utc().date();
+                items.sort_by_cached_key(|habit| {
+                    let hours = self.model.data(habit.id).hours(&TimeFrame::Week, today);
+                    std::cmp::Reverse((hours * 60.0) as u64)
+                })
+            }
+            SortMode::Due => items.sort_by_cached_key(|habit| self.today_status(habit).urgency()),
+        }
+        // habits are grouped under their category, uncategorised ones last
+        items.sort_by_key(|habit| (habit.category.is_none(), habit.category.clone()));
+        self.habits.items = items;
+        if let Some(selected) = self.habits.state.selected()
+            && selected >= self.habits.items.len()
+        {
+            self.habits
+                .state
+                .select(self.habits.items.len().checked_sub(1));
+        }
+        labels
+    }
+
+    /// Whether the habit is done, due or not due today.
+    pub fn today_status(&self, habit: &user_habits::HabitItem) -> TodayStatus {
+        let today = OffsetDateTime::now_utc().date();
+        let last = self.model.data(habit.id).last;
+        TodayStatus::from_last(last, habit.frequency, habit.quit, today)
+    }
+
+    /// Habits due or done today, unfinished ones first under their schedules.
+    pub fn today_checklist(&self) -> Vec<ChecklistItem> {
+        let today = OffsetDateTime::now_utc().date();
+        // every habit, whatever the list is filtered or sorted by
+        let mut items: Vec<ChecklistItem> = self
+            .model
+            .habits
+            .iter()
+            .filter(|habit| habit.active && !habit.quit)
+            .filter_map(|habit| {
+                let done = match self.today_status(habit) {
+                    TodayStatus::Done => true,
+                    TodayStatus::Due => false,
+                    _ => return None,
+                };
+                let (hours, logged) = self.model.data(habit.id).on(today);
+                Some(ChecklistItem {
+                    habit: habit.clone(),
+                    done,
+                    logged,
+                    hours,
+                })
+            })
+            .collect();
+        items.sort_by_key(|item| (item.done, item.habit.frequency));
+        items
+    }
+
+    /// Full screen list of today's habits with a box for logging hours.
+    fn render_checklist(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let items = self.today_checklist();
+        let done = items.iter().filter(|item| item.done).count();
+        let today = OffsetDateTime::now_utc().date();
+        let block = Block::new()
+            .title(
+                Line::from(format!("Today {today}  {done}/{} done", items.len()))
+                    .bold()
+                    .blue()
+                    .centered(),
+            )
+            .title_bottom(Line::from(self.status.clone()).italic().left_aligned())
+            .title_bottom(
+                Line::from("↑/↓: select | Space: check | hours then Enter: log | Esc: all habits")
+                    .right_aligned(),
+            )
+            .borders(Borders::ALL)
+            .border_style(SELECTED_STYLE);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .split(area);
+
+        let mut rows = Vec::with_capacity(items.len());
+        let mut list_items: Vec<ListItem> = Vec::new();
+        for (i, item) in items.iter().enumerate() {
+            let previous = i.checked_sub(1).map(|p| &items[p]);
+            // unfinished habits sit under their schedule, finished ones under "done"
+            let header = if item.done {
+                previous.is_none_or(|p| !p.done).then(|| "done".to_string())
+            } else {
+                previous
+                    .is_none_or(|p| p.habit.frequency != item.habit.frequency)
+                    .then(|| checklist::schedule(item.habit.frequency))
+            };
+            if let Some(header) = header {
+                list_items.push(
+                    ListItem::from(Line::from(format!("── {header} ──")).bold())
+                        .style(my_colors::NORMAL_STYLE),
+                );
+            }
+            rows.push(list_items.len());
+            let list_item = ListItem::from(item.label()).bg(alternate_colors(i));
+            list_items.push(if item.done {
+                list_item.style(my_colors::NORMAL_STYLE.dim())
+            } else {
+                list_item
+            });
+        }
+        if items.is_empty() {
+            list_items.push(ListItem::from("Nothing due today"));
+        }
+        let list = List::new(list_items)
+            .block(block)
+            .highlight_style(my_colors::SELECTED_STYLE)
+            .highlight_symbol(">>")
+            .highlight_spacing(ratatui::widgets::HighlightSpacing::Always);
+        if let Some(selected) = self.checklist_state.selected()
+            && selected >= items.len()
+        {
+            self.checklist_state.select(items.len().checked_sub(1));
+        }
+        // the list has header rows, so the selected item is mapped to its row
+        let mut list_state = ListState::default()
+            .with_offset(self.checklist_state.offset())
+            .with_selected(self.checklist_state.selected().map(|idx| rows[idx]));
+        frame.render_stateful_widget(list, chunks[0], &mut list_state);
+        *self.checklist_state.offset_mut() = list_state.offset();
+
+        let hours = Paragraph::new(self.habit_hours_buffer.content.as_str()).block(
+            Block::new()
+                .title("Hours and note (e.g. 1.5 long run)")
+                .borders(Borders::ALL)
+                .border_style(my_colors::NORMAL_STYLE),
+        );
+        frame.render_widget(hours, chunks[1]);
+    }
+
+    /// Columns hidden in an earlier session.
+    fn load_hidden_columns(&self) -> Vec<HabitColumn> {
+        self.db
+            .get_setting("hidden_columns")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(HabitColumn::from_key)
+            .collect()
     }
 
-    pub fn habit_list_block<'a>(
-        items: &'a [user_habits::HabitItem],
-        input_mode: &InputMode,
-    ) -> (Vec<ListItem<'a>>, List<'a>) {
+    /// Tabs for the tags and categories the habit list can be filtered to.
+    fn filter_bar(&self, labels: &[String]) -> Tabs<'static> {
+        let selected = self
+            .tag_filter
+            .as_ref()
+            .and_then(|filter| labels.iter().position(|label| label == filter))
+            .map_or(0, |i| i + 1);
+        let titles: Vec<String> = std::iter::once("All".to_string())
+            .chain(labels.iter().cloned())
+            .collect();
+        Tabs::new(titles)
+            .select(selected)
+            .highlight_style(SELECTED_STYLE)
+            .block(
+                Block::new()
+                    .title(Line::from("Filter (f)").italic())
+                    .borders(Borders::ALL)
+                    .border_style(my_colors::NORMAL_STYLE),
+            )
+    }
+
+    /// The habit table with a header row above each category. Also returns the row
+    /// each habit is drawn on.
+    pub fn habit_list_block(&self) -> (Vec<usize>, Table<'static>) {
         let habit_list = Line::from("Habit List").bold().blue().centered();
         // .style(Style::new().fg(convert_color_type(PALETTE.macchiato.colors.blue)));
 
-        let border_style = if *input_mode == InputMode::Normal {
+        let selecting = self.input_mode == InputMode::Selecting;
+        let border_style = if self.input_mode == InputMode::Normal || selecting {
             SELECTED_STYLE
         } else {
             Style::new().fg(my_colors::BORDER_COL)
@@ -138,119 +486,904 @@ impl App {
 
         let block = Block::new()
             .title(habit_list)
+            .title_bottom(if selecting {
+                Line::from("Space pick · a all · Enter mark done · Esc cancel")
+                    .italic()
+                    .right_aligned()
+            } else {
+                Line::from(self.status.clone()).italic().right_aligned()
+            })
+            .title_bottom(
+                self.timer
+                    .as_ref()
+                    .map(|timer| {
+                        Line::from(timer.display(Instant::now()))
+                            .bold()
+                            .left_aligned()
+                    })
+                    .unwrap_or_default(),
+            )
             .borders(Borders::ALL)
             .border_style(border_style);
 
-        let items: Vec<ListItem> = items
-            .iter()
-            .enumerate()
-            .map(|(i, list_item)| {
-                let color = alternate_colors(i);
-                let item = ListItem::from(list_item.name.clone()).bg(color);
-                // If habit has a streak > 5, style it orange
-                if list_item.current_streak > 5 {
-                    item.style(my_colors::STREAK_STYLE)
-                } else {
-                    item
-                }
-            })
+        let columns: Vec<HabitColumn> = HabitColumn::ALL
+            .into_iter()
+            .filter(|column| !self.habits.hidden_columns.contains(column))
             .collect();
-        let list = List::new(items.clone())
+        let today = OffsetDateTime::now_utc().date();
+        let items = &self.habits.items;
+        let grouped = items.iter().any(|habit| habit.category.is_some());
+        let mut rows = Vec::with_capacity(items.len());
+        let mut table_rows: Vec<Row> = Vec::new();
+        for (i, habit) in items.iter().enumerate() {
+            if grouped && (i == 0 || items[i - 1].category != habit.category) {
+                let header = habit.category.as_deref().unwrap_or("other");
+                table_rows.push(
+                    Row::new([Cell::from(Line::from(format!("── {header} ──")).bold())])
+                        .style(my_colors::NORMAL_STYLE),
+                );
+            }
+            rows.push(table_rows.len());
+            let label = match (&habit.challenge, habit.challenge_result) {
+                (_, Some(outcome)) => format!("{}  {outcome}", habit.name),
+                (Some(challenge), None) => match challenge.days_left(today) {
+                    Some(days) => format!("{}  ⏳ {days}d left", habit.name),
+                    None => format!("{}  ⏳", habit.name),
+                },
+                (None, None) => habit.name.clone(),
+            };
+            let label = match (selecting, self.picked.contains(&habit.id)) {
+                (true, true) => format!("[x] {label}"),
+                (true, false) => format!("[ ] {label}"),
+                (false, _) => label,
+            };
+            let mut cells = vec![Cell::from(label)];
+            for column in &columns {
+                cells.push(Cell::from(self.habit_cell(habit, *column, today)));
+            }
+            let row = Row::new(cells).bg(alternate_colors(i));
+            // If habit has a streak > 5, style it orange
+            table_rows.push(if habit.current_streak > 5 {
+                row.style(my_colors::STREAK_STYLE)
+            } else {
+                row
+            });
+        }
+        let mut widths = vec![Constraint::Min(10)];
+        widths.extend(
+            columns
+                .iter()
+                .map(|column| Constraint::Length(column.width())),
+        );
+        let mut titles = vec!["Habit"];
+        titles.extend(columns.iter().map(|column| column.title()));
+        let table = Table::new(table_rows, widths)
+            .header(Row::new(titles).bold().style(my_colors::NORMAL_STYLE))
             .block(block)
-            .highlight_style(my_colors::SELECTED_STYLE)
+            .row_highlight_style(my_colors::SELECTED_STYLE)
             .highlight_symbol(">>")
             .highlight_spacing(ratatui::widgets::HighlightSpacing::Always);
 
-        (items, list)
+        (rows, table)
+    }
+
+    /// Text of one optional column for a habit.
+    fn habit_cell(
+        &self,
+        habit: &user_habits::HabitItem,
+        column: HabitColumn,
+        today: Date,
+    ) -> String {
+        match column {
+            HabitColumn::Streak => format!("{}/{}", habit.current_streak, habit.max_streak),
+            HabitColumn::WeekHours => format!(
+                "{:.1}h",
+                self.model.data(habit.id).hours(&TimeFrame::Week, today)
+            ),
+            HabitColumn::Sparkline => {
+                let start = today - time::Duration::days(6);
+                let data = self.model.data(habit.id);
+                let daily = if habit.quit {
+                    data.clean_between(start, today + time::Duration::days(1), today)
+                } else {
+                    data.between(start, today + time::Duration::days(1))
+                };
+                // hours when any were logged this week, otherwise how often it was done
+                let by_hours = daily.iter().any(|(_, hours, _)| *hours > 0.0);
+                let values: Vec<f64> = streak::days_between(start, today)
+                    .iter()
+                    .map(|day| {
+                        daily.iter().find(|(date, _, _)| date == day).map_or(
+                            0.0,
+                            |(_, hours, count)| {
+                                if by_hours { *hours } else { f64::from(*count) }
+                            },
+                        )
+                    })
+                    .collect();
+                habit_table::sparkline(&values)
+            }
+            HabitColumn::Today => habit_table::today_marker(self.today_status(habit)).to_string(),
+        }
     }
     // this function needs a habit selected. So there must be data related to a habit
     pub fn habit_calendar_tracker_block<'a>(
-        &self,
+        &'a self,
         habit_calendar_titile_block: &Block<'a>,
-    ) -> Option<calendar::Monthly<'a, CompletedDateStyler>> {
-        let date = OffsetDateTime::now_utc().date();
-        let selected_idx = self.habits.state.selected();
-
-        if let Some(idx) = selected_idx {
-            let habit = &self.habits.items[idx];
-            let habit_id = habit.id;
-            let frequency = habit.frequency;
-            let current_streak = habit.current_streak;
-
-            let completed_dates = self.db.list_completed_dates(habit_id);
-            let streak_dates = self
-                .db
-                .list_streak_dates(habit_id, frequency, current_streak);
-
-            let mut date_styled_cal = CompletedDateStyler::new();
-            date_styled_cal
-                .update_dates(completed_dates)
-                .expect("updated completed dates");
-            date_styled_cal
-                .update_streak_dates(streak_dates)
-                .expect("updated streak dates");
-
-            let cal = calendar::Monthly::new(date, date_styled_cal)
-                .block(habit_calendar_titile_block.clone())
-                .show_month_header(Style::new().bold())
-                .show_weekdays_header(Style::new().italic());
-            return Some(cal);
+    ) -> Option<calendar::Monthly<'a, &'a CompletedDateStyler>> {
+        let date = self.calendar_month(self.calendar_month_offset);
+        let date_styled_cal = self.habit_date_styler()?;
+        let legend = date_styled_cal.hours_legend().right_aligned();
+        let cal = calendar::Monthly::new(date, date_styled_cal)
+            .block(habit_calendar_titile_block.clone().title_bottom(legend))
+            .show_month_header(Style::new().bold())
+            .show_weekdays_header(Style::new().italic());
+        Some(cal)
+    }
+
+    /// Renders `months` calendars in a grid, oldest first, ending at the displayed month.
+    fn render_month_grid(
+        &self,
+        frame: &mut Frame,
+        area: ratatui::layout::Rect,
+        block: Block<'_>,
+        months: u8,
+    ) {
+        let inner = block.inner(area);
+        let Some(date_styled_cal) = self.habit_date_styler() else {
+            frame.render_widget(block, area);
+            return;
+        };
+        frame.render_widget(
+            block.title_bottom(date_styled_cal.hours_legend().right_aligned()),
+            area,
+        );
+
+        let (rows, cols) = CalendarView::grid_shape(months);
+        let row_areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Ratio(1, rows.into()); rows.into()])
+            .split(inner);
+        for (row, row_area) in row_areas.iter().enumerate() {
+            let cells = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, cols.into()); cols.into()])
+                .split(*row_area);
+            for (col, cell) in cells.iter().enumerate() {
+                let position = (row * cols as usize + col) as i32;
+                let offset = self.calendar_month_offset + i32::from(months) - 1 - position;
+                let cal = calendar::Monthly::new(self.calendar_month(offset), date_styled_cal)
+                    .show_month_header(Style::new().bold())
+                    .show_weekdays_header(Style::new().italic());
+                frame.render_widget(cal, *cell);
+            }
+        }
+    }
+
+    /// The date styler for the selected habit's completions and streak.
+    fn habit_date_styler(&self) -> Option<&CompletedDateStyler> {
+        let idx = self.habits.state.selected()?;
+        Some(&self.model.data(self.habits.items[idx].id).styler)
+    }
+
+    /// First day of the month `offset` months before the current one.
+    pub fn calendar_month(&self, offset: i32) -> Date {
+        history::add_months(OffsetDateTime::now_utc().date(), -offset)
+    }
+
+    /// Year-long contribution graph for the selected habit.
+    pub fn habit_heatmap_block<'a>(&self, block: Block<'a>) -> Option<YearHeatmap<'a>> {
+        let idx = self.habits.state.selected()?;
+        let habit = &self.habits.items[idx];
+        let start = Date::from_calendar_date(self.heatmap_year, time::Month::January, 1).ok()?;
+        let end = history::add_months(start, 12);
+        let data = self.model.data(habit.id);
+        if habit.quit {
+            let today = OffsetDateTime::now_utc().date();
+            let values: HashMap<Date, u32> = data
+                .clean_between(start, end, today)
+                .into_iter()
+                .map(|(date, _, count)| (date, count))
+                .collect();
+            let block = block
+                .title(
+                    Line::from(format!(
+                        "< {} {} (clean days) >",
+                        habit.name, self.heatmap_year
+                    ))
+                    .left_aligned(),
+                )
+                .title_bottom(YearHeatmap::legend().right_aligned());
+            return Some(YearHeatmap::new(self.heatmap_year, values).block(block));
+        }
+        let values: HashMap<Date, u32> = data
+            .between(start, end)
+            .into_iter()
+            .map(|(date, hours, count)| {
+                // shade by minutes so short sessions still register
+                let value = if self.heatmap_by_hours {
+                    (hours * 60.0).round() as u32
+                } else {
+                    count
+                };
+                (date, value)
+            })
+            .collect();
+        let unit = if self.heatmap_by_hours {
+            "hours"
+        } else if habit.is_counter() {
+            "entries"
         } else {
-            return None;
+            "completions"
+        };
+        let block = block
+            .title(
+                Line::from(format!("< {} {} ({unit}) >", habit.name, self.heatmap_year))
+                    .left_aligned(),
+            )
+            .title_bottom(YearHeatmap::legend().right_aligned());
+        Some(YearHeatmap::new(self.heatmap_year, values).block(block))
+    }
+
+    /// Bar chart of hours, completions or a metric per week or month, as many periods as fit in `width`.
+    pub fn habit_stats_tracker(&self, width: u16) -> Option<BarChart<'_>> {
+        let idx = self.get_current_habit()?;
+        let count = u32::from(width.saturating_sub(2) / (STATS_BAR_WIDTH + STATS_BAR_GAP)).max(1);
+        let today = OffsetDateTime::now_utc().date();
+        let starts = history::period_starts(today, &self.stats_timeframe, count, self.stats_offset);
+        let end = history::next_period(*starts.last()?, &self.stats_timeframe);
+        let (daily, aggregate, unit) = self.chart_series(idx, &starts[0], &end);
+        let totals = history::bucket(&daily, &starts, &self.stats_timeframe);
+
+        // bar heights are in tenths so short sessions and fractional values still show
+        let bars_values: Vec<u64> = totals
+            .iter()
+            .map(|total| (period_value(total, aggregate) * 10.0).round() as u64)
+            .collect();
+        let bars: Vec<Bar> = totals
+            .iter()
+            .zip(&bars_values)
+            .map(|(total, value)| {
+                let label = match self.stats_timeframe {
+                    TimeFrame::Week => format!(
+                        "{:02}/{:02}",
+                        u8::from(total.start.month()),
+                        total.start.day()
+                    ),
+                    _ => format!(
+                        "{}{:02}",
+                        &total.start.month().to_string()[..3],
+                        total.start.year() % 100
+                    ),
+                };
+                let text = if self.stats_value == ChartValue::Completions {
+                    total.count.to_string()
+                } else {
+                    format!("{:.1}", period_value(total, aggregate))
+                };
+                self.vertical_bar(*value, text, label)
+            })
+            .collect();
+        let title = format!(
+            "Habit Stats: {unit} per {}",
+            self.stats_timeframe.to_string().to_lowercase()
+        );
+        let summary = [TimeFrame::Week, TimeFrame::Month, TimeFrame::Year]
+            .into_iter()
+            .map(|tf| format!("{tf} {:.1}h", self.model.data(idx).hours(&tf, today)))
+            .collect::<Vec<String>>()
+            .join(" | ");
+        let mut chart = self.vertical_barchart(bars, title, summary);
+        // scale to the target so a full bar means the goal was met
+        let target = self
+            .habits
+            .state
+            .selected()
+            .and_then(|i| self.habits.items[i].target(self.stats_timeframe));
+        if let Some(target) = target.filter(|_| self.stats_value == ChartValue::Hours) {
+            let largest = bars_values.iter().copied().max().unwrap_or(0);
+            chart = chart.max(largest.max((f64::from(target) * 10.0).round() as u64));
         }
+        Some(chart)
     }
-    pub fn habit_stats_tracker(&self) -> Option<BarChart<'_>> {
-        let idx = self.get_current_habit();
-        if idx.is_some() {
-            let hours_array = vec![
-                self.db.get_hours(idx.unwrap(), crate::db::TimeFrame::Week),
-                self.db.get_hours(idx.unwrap(), crate::db::TimeFrame::Month),
-                self.db.get_hours(idx.unwrap(), crate::db::TimeFrame::Year),
-            ];
-            let labels_array: Vec<String> = vec![
-                crate::db::TimeFrame::Week.to_string(),
-                crate::db::TimeFrame::Month.to_string(),
-                crate::db::TimeFrame::Year.to_string(),
-            ];
-            let max_hours: Vec<u32> = vec![40, 200, 1000];
-            return Some(self.vertical_barchart(&hours_array, &labels_array, &max_hours));
+
+    /// Daily `(date, value, samples)` rows in `[start, end)` for what the stats panel
+    /// is charting, with how they combine and a label for them.
+    fn chart_series(
+        &self,
+        habit_id: u64,
+        start: &Date,
+        end: &Date,
+    ) -> (Vec<(Date, f64, u32)>, Aggregate, String) {
+        let data = self.model.data(habit_id);
+        if let ChartValue::Metric(i) = self.stats_value
+            && let Some((metric, values)) = data.metrics.get(i)
+        {
+            let values = values
+                .iter()
+                .filter(|(date, _)| date >= start && date < end)
+                .map(|(date, value)| (*date, *value, 1))
+                .collect();
+            return (
+                values,
+                metric.aggregate,
+                format!("{} ({})", metric.name, metric.unit),
+            );
+        }
+        if self.model.habit(habit_id).is_some_and(|habit| habit.quit) {
+            let today = OffsetDateTime::now_utc().date();
+            let clean = data.clean_between(*start, *end, today);
+            return (clean, Aggregate::Sum, "Clean days".to_string());
+        }
+        let daily = data.between(*start, *end);
+        if self.stats_value == ChartValue::Completions {
+            let counts = daily
+                .into_iter()
+                .map(|(date, _, count)| (date, f64::from(count), count))
+                .collect();
+            // counter habits log several entries a day; plot those rather than full days
+            let unit = if self
+                .model
+                .habit(habit_id)
+                .is_some_and(user_habits::HabitItem::is_counter)
+            {
+                "Entries"
+            } else {
+                "Completions"
+            };
+            (counts, Aggregate::Sum, unit.to_string())
         } else {
-            return None;
+            (daily, Aggregate::Sum, "Hours".to_string())
         }
     }
 
-    /// Create a vertical bar chart from the valuess data.
-    fn vertical_barchart(
+    /// Create a vertical bar chart from the bars, scaled to the largest one.
+    fn vertical_barchart<'a>(
         &self,
-        values: &[u32],
-        labels: &[String],
-        max_hours: &[u32],
-    ) -> BarChart<'_> {
-        let zipped: Vec<(&u32, &String)> = values.iter().zip(labels).collect();
-        let bars: Vec<Bar> = zipped
-            .iter()
-            .map(|(value, label)| self.vertical_bar(value, label))
+        bars: Vec<Bar<'a>>,
+        title: String,
+        totals: String,
+    ) -> BarChart<'a> {
+        let block = self
+            .stats_block(title, "< older  newer >")
+            .title_bottom(Line::from(totals).left_aligned());
+
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .block(block)
+            .bar_width(STATS_BAR_WIDTH)
+            .bar_gap(STATS_BAR_GAP)
+    }
+
+    /// Progress gauges for the selected habit's hour targets; returns the height used.
+    fn render_target_gauges(&self, frame: &mut Frame, area: ratatui::layout::Rect) -> u16 {
+        let Some(idx) = self.habits.state.selected() else {
+            return 0;
+        };
+        let habit = &self.habits.items[idx];
+        let targets: Vec<(TimeFrame, f32)> = [TimeFrame::Week, TimeFrame::Month, TimeFrame::Year]
+            .into_iter()
+            .filter_map(|tf| Some((tf, habit.target(tf)?)))
+            .filter(|(_, target)| *target > 0.0)
             .collect();
+        if targets.is_empty() {
+            return 0;
+        }
 
-        let habit_stats_title = Line::from("Habit Stats").bold().blue().centered();
+        let height = targets.len() as u16 + 2;
         let block = Block::new()
-            .title(habit_stats_title)
+            .title(Line::from("Targets").bold().blue().centered())
             .borders(Borders::ALL)
-            .border_style(my_colors::BORDER_COL);
+            .border_style(my_colors::NORMAL_STYLE);
+        let gauges_area = Rect {
+            height: height.min(area.height),
+            ..area
+        };
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1); targets.len()])
+            .split(block.inner(gauges_area));
+        frame.render_widget(block, gauges_area);
+        let today = OffsetDateTime::now_utc().date();
+        for ((tf, target), row) in targets.into_iter().zip(rows.iter()) {
+            let done = self.model.data(habit.id).hours(&tf, today);
+            let target = f64::from(target);
+            let ratio = (done / target).clamp(0.0, 1.0);
+            let label = format!(
+                "{tf:<5} {done:.1}/{target:.1}h {:>3.0}% {:.1}h left",
+                done / target * 100.0,
+                (target - done).max(0.0)
+            );
+            let gauge = LineGauge::default()
+                .ratio(ratio)
+                .label(label)
+                .filled_style(SELECTED_STYLE)
+                .unfilled_style(my_colors::NORMAL_STYLE);
+            frame.render_widget(gauge, *row);
+        }
+        height
+    }
 
-        BarChart::default()
+    /// Progress bar and countdown for the selected habit's challenge. Returns the
+    /// height used, 0 without a challenge.
+    fn render_challenge_gauge(&self, frame: &mut Frame, area: ratatui::layout::Rect) -> u16 {
+        let Some(habit) = self
+            .habits
+            .state
+            .selected()
+            .map(|idx| &self.habits.items[idx])
+        else {
+            return 0;
+        };
+        let Some(challenge) = &habit.challenge else {
+            return 0;
+        };
+        let today = OffsetDateTime::now_utc().date();
+        let done = self.model.data(habit.id).challenge_done;
+        let goal = challenge.goal(habit.frequency);
+        let mut label = format!("{done}/{goal} done");
+        match (habit.challenge_result, challenge.days_left(today)) {
+            (Some(outcome), _) => label.push_str(&format!(" · {outcome}")),
+            (None, Some(days)) => label.push_str(&format!(" · {days} days left")),
+            (None, None) => {}
+        }
+        let title = match challenge.end {
+            Some(end) => format!("Challenge {} to {end}", challenge.start),
+            None => format!("Challenge from {}", challenge.start),
+        };
+        let height = 3.min(area.height);
+        let gauge = Gauge::default()
+            .block(
+                Block::new()
+                    .title(Line::from(title).bold().blue().centered())
+                    .borders(Borders::ALL)
+                    .border_style(my_colors::NORMAL_STYLE),
+            )
+            .gauge_style(match habit.challenge_result {
+                Some(Outcome::Failed) => my_colors::SLIP_STYLE,
+                _ => SELECTED_STYLE,
+            })
+            .ratio((f64::from(done) / f64::from(goal.max(1))).clamp(0.0, 1.0))
+            .label(label);
+        frame.render_widget(gauge, Rect { height, ..area });
+        height
+    }
+
+    /// Line chart of daily (or weekly) values with 7 and 30 day moving averages.
+    fn render_trend_chart(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(habit_id) = self.get_current_habit() else {
+            frame.render_widget(self.stats_block("Trend".to_string(), ""), area);
+            return;
+        };
+
+        let days: i64 = if self.trend_weekly { 52 * 7 } else { 90 };
+        let today = OffsetDateTime::now_utc().date();
+        let first = today - time::Duration::days(days - 1);
+        // fetch a month earlier so the long average is warmed up on the first plotted day
+        let warm_up = first - time::Duration::days(trend::LONG_WINDOW as i64);
+        let (series, aggregate, unit) =
+            self.chart_series(habit_id, &warm_up, &(today + time::Duration::days(1)));
+        let per = if self.trend_weekly { "week" } else { "day" };
+        let block = self.stats_block(format!("Trend: {unit} per {per}"), "d: day/week");
+
+        // averaged metrics skip days without a value; sums count them as zero
+        let missing = match aggregate {
+            Aggregate::Sum => Some(0.0),
+            Aggregate::Average => None,
+        };
+        let mut daily = vec![missing; (today - warm_up).whole_days() as usize + 1];
+        for (date, value, _) in series {
+            daily[(date - warm_up).whole_days() as usize] = Some(value);
+        }
+        let short = trend::moving_average(&daily, trend::SHORT_WINDOW);
+        let long = trend::moving_average(&daily, trend::LONG_WINDOW);
+        let direction = Trend::from_averages(
+            short.last().copied().unwrap_or(0.0),
+            long.last().copied().unwrap_or(0.0),
+        );
+
+        // plotted points start after the warm-up; weekly mode sums each week and
+        // scales the averages to a weekly rate so every line shares one axis
+        let skip = trend::LONG_WINDOW;
+        let (values, short_points, long_points): (Points, Points, Points) = if self.trend_weekly {
+            let scale = match aggregate {
+                Aggregate::Sum => 7.0,
+                Aggregate::Average => 1.0,
+            };
+            let values = daily[skip..]
+                .chunks(7)
+                .enumerate()
+                .map(|(i, week)| {
+                    let total = trend::moving_average(week, 7)
+                        .last()
+                        .copied()
+                        .unwrap_or(0.0);
+                    (i as f64, total * scale)
+                })
+                .collect();
+            let sample = |averages: &[f64]| {
+                averages[skip..]
+                    .chunks(7)
+                    .enumerate()
+                    .map(|(i, week)| (i as f64, week[week.len() - 1] * scale))
+                    .collect()
+            };
+            (values, sample(&short), sample(&long))
+        } else {
+            let values = daily[skip..]
+                .iter()
+                .enumerate()
+                .filter_map(|(i, value)| Some((i as f64, (*value)?)))
+                .collect();
+            let points = |series: &[f64]| {
+                series[skip..]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| (i as f64, *value))
+                    .collect()
+            };
+            (values, points(&short), points(&long))
+        };
+
+        let max_y = values
+            .iter()
+            .chain(&short_points)
+            .chain(&long_points)
+            .map(|(_, y)| *y)
+            .fold(0.0, f64::max)
+            .max(1.0);
+        let max_x = if self.trend_weekly {
+            (values.len().max(2) - 1) as f64
+        } else {
+            (days - 1) as f64
+        };
+        let datasets = vec![
+            Dataset::default()
+                .name(unit.clone())
+                .marker(symbols::Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(my_colors::NORMAL_STYLE)
+                .data(&values),
+            Dataset::default()
+                .name("7 day avg")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(my_colors::SHORT_AVERAGE_COL))
+                .data(&short_points),
+            Dataset::default()
+                .name("30 day avg")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(my_colors::LONG_AVERAGE_COL))
+                .data(&long_points),
+        ];
+        let chart = Chart::new(datasets)
+            .block(block.title(Line::from(direction.to_string()).right_aligned()))
+            .x_axis(
+                Axis::default()
+                    .bounds([0.0, max_x])
+                    .labels([first.to_string(), today.to_string()])
+                    .style(my_colors::NORMAL_STYLE),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([0.0, max_y])
+                    .labels([
+                        "0".to_string(),
+                        format!("{:.1}", max_y / 2.0),
+                        format!("{max_y:.1}"),
+                    ])
+                    .style(my_colors::NORMAL_STYLE),
+            );
+        frame.render_widget(chart, area);
+    }
+
+    /// Completion rates, weekday patterns and session figures for the selected habit.
+    fn render_habit_summary(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let block = self.stats_block("Statistics".to_string(), "");
+        let Some(idx) = self.habits.state.selected() else {
+            frame.render_widget(block, area);
+            return;
+        };
+        let habit = &self.habits.items[idx];
+        let today = OffsetDateTime::now_utc().date();
+        let data = self.model.data(habit.id);
+        if habit.quit {
+            self.render_quit_summary(frame, area, block, habit, today);
+            return;
+        }
+        let summary = HabitStats::compute(
+            &data.daily,
+            data.sessions,
+            habit.frequency,
+            habit.daily_target,
+            today,
+        );
+
+        let row = |label: &str, value: String| {
+            Line::from(vec![
+                Span::raw(format!("{label:<24}")).bold(),
+                Span::raw(value),
+            ])
+        };
+        let weekday = |day: Option<time::Weekday>| day.map_or("-".to_string(), |d| d.to_string());
+        let mut lines = vec![Line::from(habit.name.clone()).bold().centered()];
+        for (window, rate) in stats::RATE_WINDOWS.iter().zip(summary.completion_rates) {
+            lines.push(row(
+                &format!("Completion ({window} days)"),
+                format!("{:.0}%", rate * 100.0),
+            ));
+        }
+        lines.push(row("Best weekday", weekday(summary.best_weekday)));
+        lines.push(row("Worst weekday", weekday(summary.worst_weekday)));
+        lines.push(row("Longest gap", format!("{} days", summary.longest_gap)));
+        lines.push(row(
+            "Average session",
+            format!("{:.1} hours", summary.average_session_hours),
+        ));
+        lines.push(row(
+            "Total completions",
+            match summary.first_completed {
+                Some(first) if habit.is_counter() => format!(
+                    "{} days since {first} ({} entries)",
+                    summary.total_completions, summary.total_entries
+                ),
+                Some(first) => format!("{} since {first}", summary.total_completions),
+                None => "0".to_string(),
+            },
+        ));
+
+        for (metric, values) in &data.metrics {
+            let values: Vec<f64> = values.iter().map(|(_, value)| *value).collect();
+            let total: f64 = values.iter().sum();
+            let average = if values.is_empty() {
+                0.0
+            } else {
+                total / values.len() as f64
+            };
+            let value = match metric.aggregate {
+                Aggregate::Sum => format!("{total:.1} {} total, {average:.1} per day", metric.unit),
+                Aggregate::Average => format!("{average:.1} {} on average", metric.unit),
+            };
+            lines.push(row(&metric.name, value));
+        }
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Clean-day rates and slips for a quit habit.
+    fn render_quit_summary(
+        &self,
+        frame: &mut Frame,
+        area: ratatui::layout::Rect,
+        block: Block,
+        habit: &user_habits::HabitItem,
+        today: Date,
+    ) {
+        let data = self.model.data(habit.id);
+        let since = data.quit_since.unwrap_or(today);
+        let summary = QuitStats::compute(&data.dates, since, today);
+        let row = |label: &str, value: String| {
+            Line::from(vec![
+                Span::raw(format!("{label:<24}")).bold(),
+                Span::raw(value),
+            ])
+        };
+        let mut lines = vec![Line::from(habit.name.clone()).bold().centered()];
+        for (window, rate) in stats::RATE_WINDOWS.iter().zip(summary.clean_rates) {
+            lines.push(row(
+                &format!("Clean ({window} days)"),
+                format!("{:.0}%", rate * 100.0),
+            ));
+        }
+        lines.push(row("Slips", format!("{} since {since}", summary.slips)));
+        lines.push(row(
+            "Most slips on",
+            summary
+                .worst_weekday
+                .map_or("-".to_string(), |day| day.to_string()),
+        ));
+        lines.push(row(
+            "Longest clean run",
+            format!("{} days", summary.longest_clean),
+        ));
+        lines.push(row(
+            "Last slip",
+            summary
+                .last_slip
+                .map_or("never".to_string(), |date| date.to_string()),
+        ));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Hours (or completions) per tag and category in the stats timeframe.
+    fn render_tag_stats(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let today = OffsetDateTime::now_utc().date();
+        let start = history::period_starts(today, &self.stats_timeframe, 1, self.stats_offset)[0];
+        let end = history::next_period(start, &self.stats_timeframe);
+        let completions = self.stats_value == ChartValue::Completions;
+        // the same habits as the list, leaving out quit habits: slips are not
+        // effort put into a tag
+        let habits: Vec<&user_habits::HabitItem> = self
+            .model
+            .habits
+            .iter()
+            .filter(|habit| (habit.active || self.habits.show_archived) && !habit.quit)
+            .collect();
+        let totals: Vec<(f64, u32)> = habits
+            .iter()
+            .map(|habit| {
+                let target = habit.daily_target.max(1);
+                // completions are full days, as in the habit's own stats
+                self.model.data(habit.id).between(start, end).iter().fold(
+                    (0.0, 0),
+                    |(hours, count), (_, h, entries)| {
+                        (hours + h, count + u32::from(*entries >= target))
+                    },
+                )
+            })
+            .collect();
+        let bars: Vec<Bar> = self
+            .model
+            .labels
+            .iter()
+            .map(|label| {
+                let (hours, count) = habits
+                    .iter()
+                    .zip(&totals)
+                    .filter(|(habit, _)| habit.has_label(label))
+                    .fold((0.0, 0), |(hours, count), (_, (h, c))| {
+                        (hours + h, count + c)
+                    });
+                let (value, text) = if completions {
+                    (u64::from(count), count.to_string())
+                } else {
+                    ((hours * 10.0).round() as u64, format!("{hours:.1}h"))
+                };
+                self.vertical_bar(value, text, label.clone())
+            })
+            .collect();
+
+        let title = format!(
+            "{} per tag · {} of {start}",
+            if completions { "Completions" } else { "Hours" },
+            self.stats_timeframe
+        );
+        let chart = BarChart::default()
             .data(BarGroup::default().bars(&bars))
-            .block(block)
-            .bar_width(5)
-            .bar_gap(10)
-            .max(max_hours[2].into())
+            .block(self.stats_block(title, "< older  newer >"))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0);
+        frame.render_widget(chart, area);
     }
 
-    fn vertical_bar(&self, value: &u32, label: &String) -> Bar<'_> {
+    /// Overview of every active habit: today's status, this week's completion,
+    /// the longest streaks and correlations between habits.
+    fn render_dashboard(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let block = Block::new()
+            .title(Line::from("Dashboard").bold().blue().centered())
+            .title_bottom(Line::from("o/Esc: close").right_aligned())
+            .borders(Borders::ALL)
+            .border_style(my_colors::NORMAL_STYLE);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let today = OffsetDateTime::now_utc().date();
+        // every active habit, whatever the list is filtered by
+        let habits: Vec<DashboardHabit> = self
+            .model
+            .habits
+            .iter()
+            .filter(|habit| habit.active)
+            .map(|habit| DashboardHabit {
+                name: habit.name.clone(),
+                frequency: habit.frequency,
+                current_streak: habit.current_streak,
+                quit: habit.quit,
+                dates: self.model.data(habit.id).dates.iter().copied().collect(),
+            })
+            .collect();
+
+        let sections = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(3),
+                Constraint::Length(5),
+                Constraint::Length(6),
+            ])
+            .split(inner);
+
+        let week = dashboard::week_completion(&habits, today);
+        let gauge = Gauge::default()
+            .block(Block::new().title("This week").borders(Borders::ALL))
+            .gauge_style(SELECTED_STYLE)
+            .ratio(week.clamp(0.0, 1.0))
+            .label(format!("{:.0}%", week * 100.0));
+        frame.render_widget(gauge, sections[0]);
+
+        let today_lines: Vec<Line> = habits
+            .iter()
+            .map(|habit| {
+                let status = habit.today_status(today);
+                let style = match status {
+                    TodayStatus::Done | TodayStatus::Clean => SELECTED_STYLE,
+                    TodayStatus::Due => my_colors::STREAK_STYLE,
+                    TodayStatus::NotDue => my_colors::NORMAL_STYLE,
+                    TodayStatus::Slipped => my_colors::SLIP_STYLE,
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:<24}", habit.name)),
+                    Span::styled(status.to_string(), style),
+                ])
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(today_lines).block(Block::new().title("Today").borders(Borders::ALL)),
+            sections[1],
+        );
+
+        let mut by_streak: Vec<&DashboardHabit> = habits.iter().collect();
+        by_streak.sort_by_key(|habit| std::cmp::Reverse(habit.current_streak));
+        let streak_lines: Vec<Line> = by_streak
+            .iter()
+            .take(3)
+            .filter(|habit| habit.current_streak > 0)
+            .map(|habit| Line::from(format!("{:<24}{}", habit.name, habit.current_streak)))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(streak_lines)
+                .block(Block::new().title("Top streaks").borders(Borders::ALL)),
+            sections[2],
+        );
+
+        let insight_lines: Vec<Line> = dashboard::correlations(&habits, today)
+            .iter()
+            .take(4)
+            .map(|correlation| Line::from(correlation.to_string()))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(insight_lines)
+                .wrap(ratatui::widgets::Wrap { trim: true })
+                .block(
+                    Block::new()
+                        .title(format!(
+                            "Insights (last {} days)",
+                            dashboard::CORRELATION_WINDOW
+                        ))
+                        .borders(Borders::ALL),
+                ),
+            sections[3],
+        );
+    }
+
+    /// Bordered block shared by the stats panel views.
+    fn stats_block(&self, title: String, hint: &str) -> Block<'static> {
+        let border_style = if self.input_mode == InputMode::ViewingStats {
+            SELECTED_STYLE
+        } else {
+            my_colors::NORMAL_STYLE
+        };
+        Block::new()
+            .title(Line::from(title).bold().blue().centered())
+            .title_bottom(
+                Line::from(if hint.is_empty() {
+                    "v: next view".to_string()
+                } else {
+                    format!("v: next view | {hint}")
+                })
+                .right_aligned(),
+            )
+            .borders(Borders::ALL)
+            .border_style(border_style)
+    }
+
+    fn vertical_bar<'a>(&self, value: u64, text: String, label: String) -> Bar<'a> {
         Bar::default()
-            .value(u64::from(*value))
-            .label(Line::from(label.clone()))
-            .text_value(format!("{value:>3}"))
+            .value(value)
+            .label(Line::from(label))
+            .text_value(text)
             .style(my_colors::NORMAL_STYLE)
             .value_style(my_colors::NORMAL_STYLE.reversed())
     }
@@ -305,16 +1438,122 @@ impl App {
         frame.render_widget(freq_paragraph, chunks[2]);
     }
 
+    fn display_edit_habit(
+        &self,
+        frame: &mut Frame,
+        area: ratatui::layout::Rect,
+        field: HabitField,
+    ) {
+        let area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3)])
+            .split(area)[0];
+        let edit_block = Block::new()
+            .title(Line::from(field.title()).bold().blue().centered())
+            .title_bottom(Line::from("Enter: save | Esc: cancel").right_aligned())
+            .borders(Borders::ALL)
+            .border_style(SELECTED_STYLE);
+        let edit_paragraph = Paragraph::new(self.habit_edit_buffer.content.clone())
+            .centered()
+            .block(edit_block);
+        frame.render_widget(ratatui::widgets::Clear, area);
+        frame.render_widget(edit_paragraph, area);
+    }
+
+    /// Search box with the best matching habits and the notes matching the query.
+    fn display_search(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+        let query = self.search_buffer.content.trim();
+        let search_block = Block::new()
+            .title(
+                Line::from("Search habits and notes")
+                    .bold()
+                    .blue()
+                    .centered(),
+            )
+            .title_bottom(Line::from("↑/↓ notes | Enter: open | Esc: cancel").right_aligned())
+            .borders(Borders::ALL)
+            .border_style(SELECTED_STYLE);
+        frame.render_widget(ratatui::widgets::Clear, area);
+        frame.render_widget(
+            Paragraph::new(format!("/{}", self.search_buffer.content)).block(search_block),
+            chunks[0],
+        );
+
+        let mut lines = Vec::new();
+        if !query.is_empty() {
+            let mut habits: Vec<(i64, &str)> = self
+                .habits
+                .items
+                .iter()
+                .filter_map(|habit| Some((search::fuzzy_score(query, &habit.name)?, &*habit.name)))
+                .collect();
+            habits.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            let names: Vec<&str> = habits.iter().take(5).map(|(_, name)| *name).collect();
+            lines.push(Line::from(format!("Habits: {}", names.join(", "))).bold());
+            for (i, hit) in self.search_hits.iter().enumerate() {
+                let line = Line::from(format!(
+                    "{}  {:<12} {}",
+                    hit.date, hit.habit_name, hit.notes
+                ));
+                lines.push(if self.search_selected == Some(i) {
+                    line.style(SELECTED_STYLE.reversed())
+                } else {
+                    line
+                });
+            }
+        }
+        // keep the highlighted note in view
+        let scroll = self.search_selected.map_or(0, |i| {
+            (i + 2).saturating_sub(chunks[1].height.saturating_sub(2) as usize)
+        });
+        frame.render_widget(
+            Paragraph::new(lines).scroll((scroll as u16, 0)).block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .border_style(my_colors::NORMAL_STYLE),
+            ),
+            chunks[1],
+        );
+    }
+
     fn render_hours_input(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
         let mut border_style = my_colors::NORMAL_STYLE;
         if self.input_mode == InputMode::EnteringHours {
             border_style = SELECTED_STYLE;
         }
 
-        let hours_block = Block::new()
-            .title(Line::from("Log Hours").bold().blue().centered())
+        let metrics = self
+            .get_current_habit()
+            .map(|id| self.model.data(id).metrics.as_slice())
+            .unwrap_or_default();
+        let mut title = match self
+            .log_metric
+            .checked_sub(1)
+            .and_then(|i| metrics.get(i))
+            .map(|(metric, _)| metric)
+        {
+            Some(metric) => format!("Log {} ({})", metric.name, metric.unit),
+            None => "Log Hours".to_string(),
+        };
+        // counter habits show how many of today's entries are done
+        if let Some(habit) = self.habits.state.selected().map(|i| &self.habits.items[i])
+            && habit.is_counter()
+        {
+            let today = OffsetDateTime::now_utc().date();
+            let (_, done) = self.model.data(habit.id).on(today);
+            title = format!("{title} {done}/{}", habit.daily_target);
+        }
+        let mut hours_block = Block::new()
+            .title(Line::from(title).bold().blue().centered())
             .borders(Borders::ALL)
             .border_style(border_style);
+        if !metrics.is_empty() {
+            hours_block = hours_block.title_bottom(Line::from("↑/↓ metric").right_aligned());
+        }
 
         let hours_paragraph: Paragraph<'_> =
             Paragraph::new(self.habit_hours_buffer.content.clone())
@@ -334,6 +1573,20 @@ impl App {
     }
 }
 
+/// A period's value: the sum, or the mean per sample for averaged metrics.
+fn period_value(total: &history::PeriodTotal, aggregate: Aggregate) -> f64 {
+    match aggregate {
+        Aggregate::Average if total.count > 0 => total.value / f64::from(total.count),
+        _ => total.value,
+    }
+}
+
+/// `(x, y)` points for a chart dataset.
+type Points = Vec<(f64, f64)>;
+
+const STATS_BAR_WIDTH: u16 = 5;
+const STATS_BAR_GAP: u16 = 1;
+
 const fn alternate_colors(i: usize) -> Color {
     if i % 2 == 0 {
         my_colors::NORMAL_ROW_BG
@@ -0,0 +1,30 @@
+/// Which history view the calendar panel is showing.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum CalendarView {
+    #[default]
+    Month,
+    /// Several months side by side, ending at the displayed month.
+    MonthGrid(u8),
+    YearHeatmap,
+}
+
+impl CalendarView {
+    pub fn next(&mut self) {
+        *self = match self {
+            CalendarView::Month => CalendarView::MonthGrid(3),
+            CalendarView::MonthGrid(3) => CalendarView::MonthGrid(6),
+            CalendarView::MonthGrid(6) => CalendarView::MonthGrid(12),
+            CalendarView::MonthGrid(_) => CalendarView::YearHeatmap,
+            CalendarView::YearHeatmap => CalendarView::Month,
+        }
+    }
+
+    /// Rows and columns used to lay out a month grid.
+    pub fn grid_shape(months: u8) -> (u16, u16) {
+        match months {
+            3 => (1, 3),
+            6 => (2, 3),
+            _ => (3, 4),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+
+use time::{Date, Duration};
+
+/// A time-limited run of a habit, e.g. "30 days of X" or "20 runs by the end of June".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Challenge {
+    pub start: Date,
+    pub end: Option<Date>,
+    /// Completions needed; without one, every scheduled day up to `end` is.
+    pub target: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Succeeded,
+    Failed,
+}
+
+impl Outcome {
+    /// Value stored in the `challenge_result` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Succeeded => "succeeded",
+            Outcome::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "succeeded" => Some(Outcome::Succeeded),
+            "failed" => Some(Outcome::Failed),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Succeeded => write!(f, "✓ succeeded"),
+            Outcome::Failed => write!(f, "✗ failed"),
+        }
+    }
+}
+
+impl Challenge {
+    /// Completions needed to succeed.
+    pub fn goal(&self, frequency: u32) -> u32 {
+        match (self.target, self.end) {
+            (Some(target), _) => target,
+            (None, Some(end)) => {
+                let days = (end - self.start).whole_days() + 1;
+                (days as f64 / f64::from(frequency.max(1))).ceil().max(1.0) as u32
+            }
+            (None, None) => 1,
+        }
+    }
+
+    /// Days left including today, or `None` without an end date.
+    pub fn days_left(&self, today: Date) -> Option<i64> {
+        self.end.map(|end| ((end - today).whole_days() + 1).max(0))
+    }
+
+    /// Succeeded once the goal is reached, failed once the end date has passed without it.
+    pub fn outcome(&self, done: u32, frequency: u32, today: Date) -> Option<Outcome> {
+        if done >= self.goal(frequency) {
+            Some(Outcome::Succeeded)
+        } else if self.end.is_some_and(|end| today > end) {
+            Some(Outcome::Failed)
+        } else {
+            None
+        }
+    }
+
+    /// Last day completions count towards the challenge.
+    pub fn last_day(&self, today: Date) -> Date {
+        self.end.map_or(today, |end| end.min(today))
+    }
+
+    /// Parses `30d` (days from today), `20x` (completions), and one or two ISO dates
+    /// in any combination such as `30d 20x`. Two dates are the start and end; a
+    /// single date is the start if it has passed and the end otherwise.
+    pub fn parse(content: &str, today: Date) -> Option<Self> {
+        let format = time::format_description::well_known::Iso8601::DEFAULT;
+        let mut dates = Vec::new();
+        let mut days = None;
+        let mut target = None;
+        for part in content.split_whitespace() {
+            if let Some(count) = part.strip_suffix('d') {
+                days = Some(count.parse::<i64>().ok().filter(|d| *d > 0)?);
+            } else if let Some(count) = part.strip_suffix('x') {
+                target = Some(count.parse::<u32>().ok().filter(|t| *t > 0)?);
+            } else {
+                dates.push(Date::parse(part, &format).ok()?);
+            }
+        }
+        let (start, end) = match (dates.as_slice(), days) {
+            ([], Some(days)) => (today, Some(today + Duration::days(days - 1))),
+            ([], None) => (today, None),
+            ([date], None) if *date > today => (today, Some(*date)),
+            ([start], None) => (*start, None),
+            ([start], Some(days)) => (*start, Some(*start + Duration::days(days - 1))),
+            ([start, end], None) => (*start, Some(*end)),
+            _ => return None,
+        };
+        if end.is_none() && target.is_none() || end.is_some_and(|end| end < start) {
+            return None;
+        }
+        Some(Challenge { start, end, target })
+    }
+}
+
+/// Written in the form [`Challenge::parse`] reads back.
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.start)?;
+        if let Some(end) = self.end {
+            write!(f, " {end}")?;
+        }
+        if let Some(target) = self.target {
+            write!(f, " {target}x")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Month;
+
+    fn date(month: Month, day: u8) -> Date {
+        Date::from_calendar_date(2026, month, day).unwrap()
+    }
+
+    fn today() -> Date {
+        date(Month::March, 31)
+    }
+
+    fn parse(content: &str) -> Option<Challenge> {
+        Challenge::parse(content, today())
+    }
+
+    #[test]
+    fn days_count_from_today() {
+        let challenge = parse("30d").unwrap();
+        assert_eq!(challenge.start, today());
+        assert_eq!(challenge.end, Some(date(Month::April, 29)));
+        assert_eq!(challenge.target, None);
+        assert_eq!(challenge.goal(1), 30);
+        assert_eq!(challenge.goal(7), 5);
+    }
+
+    #[test]
+    fn a_target_needs_no_end() {
+        let challenge = parse("20x").unwrap();
+        assert_eq!((challenge.start, challenge.end), (today(), None));
+        assert_eq!(challenge.goal(1), 20);
+    }
+
+    #[test]
+    fn parts_combine_in_any_order() {
+        assert_eq!(parse("20x 30d"), parse("30d 20x"));
+        let challenge = parse("2026-03-01 10d").unwrap();
+        assert_eq!(challenge.start, date(Month::March, 1));
+        assert_eq!(challenge.end, Some(date(Month::March, 10)));
+    }
+
+    #[test]
+    fn a_single_date_is_the_end_until_it_passes() {
+        let ahead = parse("2026-06-30").unwrap();
+        assert_eq!(
+            (ahead.start, ahead.end),
+            (today(), Some(date(Month::June, 30)))
+        );
+
+        // a past date is only a start, which needs a target to finish
+        assert_eq!(parse("2026-03-01"), None);
+        let past = parse("2026-03-01 10x").unwrap();
+        assert_eq!((past.start, past.end), (date(Month::March, 1), None));
+    }
+
+    #[test]
+    fn two_dates_are_start_and_end() {
+        let challenge = parse("2026-03-01 2026-03-31").unwrap();
+        assert_eq!(challenge.start, date(Month::March, 1));
+        assert_eq!(challenge.end, Some(today()));
+        assert_eq!(parse("2026-03-31 2026-03-01"), None);
+    }
+
+    #[test]
+    fn bad_input_is_rejected() {
+        for content in [
+            "",
+            "0d",
+            "0x",
+            "-3d",
+            "tomorrow",
+            "5 days",
+            "2026-01-01 2026-02-01 2026-03-01",
+        ] {
+            assert_eq!(parse(content), None, "{content:?}");
+        }
+    }
+
+    #[test]
+    fn display_reads_back() {
+        for content in ["30d", "20x", "2026-03-01 10x", "2026-03-01 2026-04-01 5x"] {
+            let challenge = parse(content).unwrap();
+            assert_eq!(parse(&challenge.to_string()), Some(challenge));
+        }
+    }
+
+    #[test]
+    fn outcome_waits_for_the_goal_or_the_end() {
+        let challenge = parse("10d").unwrap();
+        let end = challenge.end.unwrap();
+        assert_eq!(challenge.outcome(9, 1, end), None);
+        assert_eq!(challenge.outcome(10, 1, end), Some(Outcome::Succeeded));
+        assert_eq!(
+            challenge.outcome(9, 1, end + Duration::days(1)),
+            Some(Outcome::Failed)
+        );
+        assert_eq!(challenge.days_left(today()), Some(10));
+        assert_eq!(challenge.days_left(end + Duration::days(5)), Some(0));
+    }
+}
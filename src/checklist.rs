@@ -0,0 +1,34 @@
+use crate::user_habits::HabitItem;
+
+/// A habit due today, as listed on the today checklist.
+#[derive(Debug, Clone)]
+pub struct ChecklistItem {
+    pub habit: HabitItem,
+    pub done: bool,
+    /// Entries logged today; only shown for counter habits.
+    pub logged: u32,
+    pub hours: f64,
+}
+
+impl ChecklistItem {
+    pub fn label(&self) -> String {
+        let check = if self.done { "[x]" } else { "[ ]" };
+        let mut label = format!("{check} {}", self.habit.name);
+        if self.habit.is_counter() {
+            label.push_str(&format!("  {}/{}", self.logged, self.habit.daily_target));
+        }
+        if self.hours > 0.0 {
+            label.push_str(&format!("  {:.1}h", self.hours));
+        }
+        label
+    }
+}
+
+/// Heading for habits repeating every `frequency` days.
+pub fn schedule(frequency: u32) -> String {
+    match frequency {
+        0 | 1 => "Daily".to_string(),
+        7 => "Weekly".to_string(),
+        days => format!("Every {days} days"),
+    }
+}
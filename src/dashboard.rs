@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use time::{Date, Duration};
+
+/// Days of history the correlations are measured over.
+pub const CORRELATION_WINDOW: i64 = 90;
+/// Both sides of a comparison need at least this many days before it is reported.
+const MIN_SAMPLE_DAYS: usize = 5;
+/// Differences smaller than this (20%) are not worth mentioning.
+const MIN_LIFT: f64 = 0.2;
+
+/// One active habit and the days it was completed, or slipped for quit habits.
+#[derive(Debug, Clone)]
+pub struct DashboardHabit {
+    pub name: String,
+    pub frequency: u32,
+    pub current_streak: u32,
+    pub quit: bool,
+    pub dates: HashSet<Date>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TodayStatus {
+    Done,
+    Due,
+    NotDue,
+    Clean,
+    Slipped,
+}
+
+impl fmt::Display for TodayStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TodayStatus::Done => write!(f, "done"),
+            TodayStatus::Due => write!(f, "due"),
+            TodayStatus::NotDue => write!(f, "not due"),
+            TodayStatus::Clean => write!(f, "clean"),
+            TodayStatus::Slipped => write!(f, "slipped"),
+        }
+    }
+}
+
+impl TodayStatus {
+    /// Done if `last` (the latest day logged, up to today) is today, due once
+    /// `frequency` days have passed since it. Quit habits are clean unless a slip
+    /// was logged today.
+    pub fn from_last(last: Option<Date>, frequency: u32, quit: bool, today: Date) -> Self {
+        match last {
+            Some(last) if last == today && quit => TodayStatus::Slipped,
+            _ if quit => TodayStatus::Clean,
+            Some(last) if last == today => TodayStatus::Done,
+            Some(last) if (today - last).whole_days() < i64::from(frequency.max(1)) => {
+                TodayStatus::NotDue
+            }
+            _ => TodayStatus::Due,
+        }
+    }
+
+    /// Sort key putting what needs doing today first.
+    pub fn urgency(&self) -> u8 {
+        match self {
+            TodayStatus::Due => 0,
+            TodayStatus::Slipped => 1,
+            TodayStatus::NotDue => 2,
+            TodayStatus::Clean => 3,
+            TodayStatus::Done => 4,
+        }
+    }
+}
+
+impl DashboardHabit {
+    pub fn today_status(&self, today: Date) -> TodayStatus {
+        let last = self.dates.iter().filter(|date| **date <= today).max();
+        TodayStatus::from_last(last.copied(), self.frequency, self.quit, today)
+    }
+}
+
+/// Share of this week's scheduled completions (Monday to today) that were done, across habits.
+/// Every day is scheduled for a quit habit and counts when no slip was logged.
+pub fn week_completion(habits: &[DashboardHabit], today: Date) -> f64 {
+    let monday = today - Duration::days(today.weekday().number_days_from_monday() as i64);
+    let days = (today - monday).whole_days() + 1;
+    let mut done = 0.0;
+    let mut expected = 0.0;
+    for habit in habits {
+        let scheduled = if habit.quit {
+            days as f64
+        } else {
+            (days as f64 / f64::from(habit.frequency.max(1))).ceil()
+        };
+        let logged = habit
+            .dates
+            .iter()
+            .filter(|date| **date >= monday && **date <= today)
+            .count() as f64;
+        let completed = if habit.quit {
+            scheduled - logged
+        } else {
+            logged
+        };
+        done += completed.min(scheduled);
+        expected += scheduled;
+    }
+    if expected == 0.0 {
+        0.0
+    } else {
+        done / expected
+    }
+}
+
+/// "`habit` is done `lift` more (or less) often on days `with` is also done".
+#[derive(Debug, Clone)]
+pub struct Correlation {
+    pub habit: String,
+    pub with: String,
+    pub lift: f64,
+}
+
+impl fmt::Display for Correlation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = if self.lift >= 0.0 { "more" } else { "less" };
+        write!(
+            f,
+            "{} is done {:.0}% {direction} often on days you also do {}",
+            self.habit,
+            self.lift.abs() * 100.0,
+            self.with
+        )
+    }
+}
+
+/// Compares how often each habit is done on days another habit was or wasn't done,
+/// strongest relationships first. Quit habits are left out.
+pub fn correlations(habits: &[DashboardHabit], today: Date) -> Vec<Correlation> {
+    let days: Vec<Date> = (0..CORRELATION_WINDOW)
+        .map(|back| today - Duration::days(back))
+        .collect();
+    let mut found = Vec::new();
+    for habit in habits {
+        for with in habits {
+            if habit.name == with.name || habit.quit || with.quit {
+                continue;
+            }
+            let (with_days, without_days): (Vec<&Date>, Vec<&Date>) =
+                days.iter().partition(|day| with.dates.contains(day));
+            if with_days.len() < MIN_SAMPLE_DAYS || without_days.len() < MIN_SAMPLE_DAYS {
+                continue;
+            }
+            let rate = |sample: &[&Date]| {
+                sample
+                    .iter()
+                    .filter(|day| habit.dates.contains(day))
+                    .count() as f64
+                    / sample.len() as f64
+            };
+            let rate_with = rate(&with_days);
+            let rate_without = rate(&without_days);
+            if rate_without == 0.0 {
+                continue;
+            }
+            let lift = rate_with / rate_without - 1.0;
+            if lift.abs() >= MIN_LIFT {
+                found.push(Correlation {
+                    habit: habit.name.clone(),
+                    with: with.name.clone(),
+                    lift,
+                });
+            }
+        }
+    }
+    found.sort_by(|a, b| b.lift.abs().total_cmp(&a.lift.abs()));
+    found
+}
@@ -1,54 +1,127 @@
-use ratatui::{style::Style, widgets::calendar::DateStyler};
+use std::collections::{HashMap, HashSet};
+
+use ratatui::{
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::calendar::DateStyler,
+};
 use time::Date;
 
-use crate::my_colors::{SELECTED_STYLE, STREAK_STYLE};
+use crate::my_colors::{
+    self, FREEZE_STYLE, HEAT_LEVELS, PARTIAL_STYLE, SELECTED_STYLE, SKIP_STYLE, SLIP_STYLE,
+    STREAK_STYLE, VACATION_STYLE,
+};
 
-#[derive(Debug, Default)]
+/// Styles calendar days from sets of typed dates, so each cell is a few hash
+/// lookups however long the history is.
+#[derive(Debug, Default, Clone)]
 pub struct CompletedDateStyler {
-    pub completed_dates: Vec<Date>,
-    pub streak_dates: Vec<Date>,
+    pub completed_dates: HashSet<Date>,
+    pub streak_dates: HashSet<Date>,
+    /// Counter habit days logged short of the daily target.
+    pub partial_dates: HashSet<Date>,
+    /// Days a quit habit slipped; shown instead of completions.
+    pub slip_dates: HashSet<Date>,
+    pub skipped_dates: HashSet<Date>,
+    pub vacation_dates: HashSet<Date>,
+    /// Missed days a streak freeze was spent on.
+    pub frozen_dates: HashSet<Date>,
+    /// Minutes logged per day, used to shade the day's background.
+    pub minutes: HashMap<Date, u32>,
+    pub max_minutes: u32,
 }
 
 impl CompletedDateStyler {
     pub fn new() -> Self {
-        CompletedDateStyler {
-            completed_dates: Vec::new(),
-            streak_dates: Vec::new(),
-        }
+        CompletedDateStyler::default()
     }
-    pub fn update_dates(&mut self, dates: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
-        for date in dates {
-            let d = Date::parse(
-                &date,
-                &time::format_description::well_known::Iso8601::DEFAULT,
-            )?;
-            self.completed_dates.push(d);
-        }
-        Ok(())
+
+    pub fn update_dates(&mut self, dates: impl IntoIterator<Item = Date>) {
+        self.completed_dates.extend(dates);
+    }
+
+    pub fn update_streak_dates(&mut self, dates: impl IntoIterator<Item = Date>) {
+        self.streak_dates.extend(dates);
+    }
+
+    pub fn update_partial_dates(&mut self, dates: impl IntoIterator<Item = Date>) {
+        self.partial_dates.extend(dates);
+    }
+
+    pub fn update_slip_dates(&mut self, dates: impl IntoIterator<Item = Date>) {
+        self.slip_dates.extend(dates);
+    }
+
+    pub fn update_skipped_dates(&mut self, dates: impl IntoIterator<Item = Date>) {
+        self.skipped_dates.extend(dates);
     }
 
-    pub fn update_streak_dates(
-        &mut self,
-        dates: Vec<String>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        for date in dates {
-            let d = Date::parse(
-                &date,
-                &time::format_description::well_known::Iso8601::DEFAULT,
-            )?;
-            self.streak_dates.push(d);
+    pub fn update_hours(&mut self, hours: impl IntoIterator<Item = (Date, f64)>) {
+        self.minutes.extend(
+            hours
+                .into_iter()
+                .map(|(date, logged)| (date, (logged * 60.0).round() as u32)),
+        );
+        self.max_minutes = self.minutes.values().copied().max().unwrap_or(0);
+    }
+
+    /// Legend for the hours shading, one swatch per heat level.
+    pub fn hours_legend(&self) -> Line<'static> {
+        let steps = (HEAT_LEVELS.len() - 1) as u32;
+        let mut spans = vec![Span::raw("Hours ")];
+        let mut previous = 0;
+        for level in 1..=steps {
+            let upper = level * self.max_minutes / steps;
+            if upper == previous {
+                continue;
+            }
+            spans.push(Span::raw(" ").bg(HEAT_LEVELS[level as usize]));
+            spans.push(Span::raw(format!("≤{:.1} ", upper as f32 / 60.0)));
+            previous = upper;
         }
-        Ok(())
+        Line::from(spans)
     }
 }
 impl DateStyler for CompletedDateStyler {
     fn get_style(&self, date: Date) -> Style {
+        if self.slip_dates.contains(&date) {
+            return SLIP_STYLE;
+        }
+        if self.partial_dates.contains(&date) {
+            return PARTIAL_STYLE;
+        }
+        let logged = self.minutes.get(&date).copied().unwrap_or(0);
+        if logged > 0 {
+            let shaded = Style::new()
+                .bg(my_colors::heat_level(logged, self.max_minutes))
+                .fg(my_colors::ALT_ROW_BG_COLOR);
+            if self.streak_dates.contains(&date) {
+                return shaded.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            }
+            return shaded;
+        }
         if self.streak_dates.contains(&date) {
             return STREAK_STYLE;
         }
         if self.completed_dates.contains(&date) {
             return SELECTED_STYLE;
         }
+        if self.frozen_dates.contains(&date) {
+            return FREEZE_STYLE;
+        }
+        if self.skipped_dates.contains(&date) {
+            return SKIP_STYLE;
+        }
+        if self.vacation_dates.contains(&date) {
+            return VACATION_STYLE;
+        }
         Style::default()
     }
 }
+
+/// Lets calendars borrow a habit's styler instead of cloning its date sets.
+impl DateStyler for &CompletedDateStyler {
+    fn get_style(&self, date: Date) -> Style {
+        (**self).get_style(date)
+    }
+}
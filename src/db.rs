@@ -1,13 +1,29 @@
-use rusqlite::{Connection, Params, Result};
+use rusqlite::{Connection, Params, Result, Row};
 use std::fmt;
 use time::Date;
 
-use crate::user_habits::HabitItem;
+use crate::challenge::{Challenge, Outcome};
+use crate::search::{self, NoteMatch};
+use crate::streak;
+use crate::user_habits::{Aggregate, HabitItem, HabitMetric, habit_calendar};
+/// Columns read by [`db::read_habit`], in order.
+const HABIT_COLUMNS: &str = "habit_id, name, active, frequency, current_streak, max_streak,
+     weekly_target, monthly_target, yearly_target, daily_target, quit, quit_since,
+     monthly_freezes, challenge_start, challenge_end, challenge_target, challenge_result, category,
+     (SELECT GROUP_CONCAT(tag, ' ') FROM habit_tags WHERE habit_tags.habit_id = habits.habit_id)";
+
+/// Restricts a `GROUP BY date_completed` query on `habit_calendar` to days with at
+/// least the habit's daily target of entries, so counter habits only count full days.
+const FULL_DAYS: &str = "HAVING COUNT(*) >= (SELECT COALESCE(daily_target, 1) FROM habits
+     WHERE habits.habit_id = habit_calendar.habit_id)";
+
 #[derive(Debug)]
 pub struct db {
     pub conn: Result<Connection>,
 }
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum TimeFrame {
+    #[default]
     Week,
     Month,
     Year,
@@ -24,7 +40,7 @@ impl fmt::Display for TimeFrame {
 
 impl db {
     pub fn new() -> Self {
-        Self {
+        let db = Self {
             conn: match Connection::open("habit-tracker.db") {
                 Ok(c) => Ok(c),
                 Err(e) => {
@@ -32,57 +48,270 @@ impl db {
                     Err(e)
                 }
             },
+        };
+        if let Ok(conn) = db.conn.as_ref() {
+            Self::migrate(conn).expect("Failed to migrate database");
+        }
+        db
+    }
+
+    /// Creates the tables on a fresh database and adds columns introduced since
+    /// the database was first created.
+    fn migrate(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS habits(habit_id integer primary key, name text,
+                 active boolean, frequency int, current_streak int, max_streak int);
+             CREATE UNIQUE INDEX IF NOT EXISTS nunique on habits(name);
+             CREATE TABLE IF NOT EXISTS habit_calendar (
+                 entry_id INTEGER PRIMARY KEY,
+                 habit_id INTEGER,
+                 date_completed TEXT,
+                 hours REAL,
+                 logged_at TEXT,
+                 notes TEXT
+             );
+             CREATE TABLE IF NOT EXISTS habit_metrics (
+                 metric_id INTEGER PRIMARY KEY,
+                 habit_id INTEGER,
+                 name TEXT,
+                 unit TEXT,
+                 aggregate TEXT DEFAULT 'sum',
+                 UNIQUE(habit_id, name)
+             );
+             CREATE TABLE IF NOT EXISTS metric_values (
+                 value_id INTEGER PRIMARY KEY,
+                 metric_id INTEGER,
+                 entry_id INTEGER,
+                 value REAL
+             );
+             CREATE TABLE IF NOT EXISTS skipped_days (
+                 habit_id INTEGER,
+                 date_skipped TEXT,
+                 UNIQUE(habit_id, date_skipped)
+             );
+             CREATE TABLE IF NOT EXISTS habit_tags (
+                 habit_id INTEGER,
+                 tag TEXT,
+                 UNIQUE(habit_id, tag)
+             );
+             CREATE TABLE IF NOT EXISTS vacations (
+                 vacation_id INTEGER PRIMARY KEY,
+                 start_date TEXT,
+                 end_date TEXT
+             );
+             CREATE TABLE IF NOT EXISTS settings (
+                 key TEXT PRIMARY KEY,
+                 value TEXT
+             );",
+        )?;
+        Self::add_column_if_missing(conn, "habits", "weekly_target", "REAL")?;
+        Self::add_column_if_missing(conn, "habits", "monthly_target", "REAL")?;
+        Self::add_column_if_missing(conn, "habits", "yearly_target", "REAL")?;
+        Self::add_column_if_missing(conn, "habits", "daily_target", "INTEGER DEFAULT 1")?;
+        Self::add_column_if_missing(conn, "habits", "quit", "BOOLEAN DEFAULT 0")?;
+        Self::add_column_if_missing(conn, "habits", "quit_since", "TEXT")?;
+        Self::add_column_if_missing(conn, "habits", "monthly_freezes", "INTEGER DEFAULT 0")?;
+        Self::add_column_if_missing(conn, "habits", "challenge_start", "TEXT")?;
+        Self::add_column_if_missing(conn, "habits", "challenge_end", "TEXT")?;
+        Self::add_column_if_missing(conn, "habits", "challenge_target", "INTEGER")?;
+        Self::add_column_if_missing(conn, "habits", "challenge_result", "TEXT")?;
+        Self::add_column_if_missing(conn, "habits", "category", "TEXT")?;
+        if !Self::has_column(conn, "habits", "position")? {
+            conn.execute_batch(
+                "ALTER TABLE habits ADD COLUMN position INTEGER;
+                 UPDATE habits SET position = habit_id;",
+            )?;
+        }
+        if !Self::has_column(conn, "habit_calendar", "logged_at")? {
+            // counter habits log several entries a day, so the old one-row-per-day
+            // table is rebuilt without its UNIQUE(habit_id, date_completed) key
+            conn.execute_batch(
+                "BEGIN;
+                 CREATE TABLE habit_calendar_entries (
+                     entry_id INTEGER PRIMARY KEY,
+                     habit_id INTEGER,
+                     date_completed TEXT,
+                     hours REAL,
+                     logged_at TEXT
+                 );
+                 INSERT INTO habit_calendar_entries(habit_id, date_completed, hours, logged_at)
+                     SELECT habit_id, date_completed, hours, date_completed FROM habit_calendar;
+                 DROP TABLE habit_calendar;
+                 ALTER TABLE habit_calendar_entries RENAME TO habit_calendar;
+                 COMMIT;",
+            )?;
         }
+        Self::add_column_if_missing(conn, "habit_calendar", "notes", "TEXT")?;
+        if !Self::has_column(conn, "metric_values", "entry_id")? {
+            // values used to be kept once per day, so a second one replaced the first;
+            // they now belong to the completion they were logged with
+            conn.execute_batch(
+                "BEGIN;
+                 CREATE TABLE metric_entry_values (
+                     value_id INTEGER PRIMARY KEY,
+                     metric_id INTEGER,
+                     entry_id INTEGER,
+                     value REAL
+                 );
+                 INSERT INTO metric_entry_values(metric_id, entry_id, value)
+                     SELECT metric_id, entry_id, value FROM (
+                         SELECT v.metric_id, v.value, (
+                             SELECT MIN(c.entry_id) FROM habit_calendar c
+                             JOIN habit_metrics m ON m.habit_id = c.habit_id
+                             WHERE m.metric_id = v.metric_id AND c.date_completed = v.date_completed
+                         ) AS entry_id
+                         FROM metric_values v
+                     ) WHERE entry_id IS NOT NULL;
+                 DROP TABLE metric_values;
+                 ALTER TABLE metric_entry_values RENAME TO metric_values;
+                 COMMIT;",
+            )?;
+        }
+        // habits deleted before their rows were deleted with them left these behind
+        conn.execute_batch(
+            "DELETE FROM metric_values WHERE metric_id NOT IN
+                 (SELECT metric_id FROM habit_metrics WHERE habit_id IN (SELECT habit_id FROM habits));
+             DELETE FROM habit_metrics WHERE habit_id NOT IN (SELECT habit_id FROM habits);
+             DELETE FROM habit_calendar WHERE habit_id NOT IN (SELECT habit_id FROM habits);
+             DELETE FROM habit_tags WHERE habit_id NOT IN (SELECT habit_id FROM habits);
+             DELETE FROM skipped_days WHERE habit_id NOT IN (SELECT habit_id FROM habits);",
+        )?;
+        // created after the rebuilds above, which would drop them
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS habit_calendar_day ON habit_calendar(habit_id, date_completed);
+             CREATE INDEX IF NOT EXISTS metric_values_metric ON metric_values(metric_id);",
+        )?;
+        // FTS5 is optional in SQLite builds; without it notes are searched with LIKE
+        if !Self::has_table(conn, "notes_fts")?
+            && conn
+                .execute(
+                    "CREATE VIRTUAL TABLE notes_fts USING fts5(
+                         notes, content='habit_calendar', content_rowid='entry_id'
+                     )",
+                    [],
+                )
+                .is_ok()
+        {
+            conn.execute_batch(
+                "CREATE TRIGGER notes_fts_insert AFTER INSERT ON habit_calendar BEGIN
+                     INSERT INTO notes_fts(rowid, notes) VALUES (new.entry_id, new.notes);
+                 END;
+                 CREATE TRIGGER notes_fts_delete AFTER DELETE ON habit_calendar BEGIN
+                     INSERT INTO notes_fts(notes_fts, rowid, notes)
+                         VALUES ('delete', old.entry_id, old.notes);
+                 END;
+                 CREATE TRIGGER notes_fts_update AFTER UPDATE ON habit_calendar BEGIN
+                     INSERT INTO notes_fts(notes_fts, rowid, notes)
+                         VALUES ('delete', old.entry_id, old.notes);
+                     INSERT INTO notes_fts(rowid, notes) VALUES (new.entry_id, new.notes);
+                 END;
+                 INSERT INTO notes_fts(notes_fts) VALUES ('rebuild');",
+            )?;
+        }
+        Ok(())
+    }
+
+    fn has_table(conn: &Connection, table: &str) -> Result<bool> {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = ?1",
+            [table],
+            |row| row.get::<_, u32>(0),
+        )
+        .map(|count| count > 0)
+    }
+
+    fn add_column_if_missing(
+        conn: &Connection,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<()> {
+        if !Self::has_column(conn, table, column)? {
+            conn.execute(
+                &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|res| res.ok())
+            .any(|name| name == column);
+        Ok(exists)
+    }
+
+    /// Maps a row selected with [`HABIT_COLUMNS`] to a [`HabitItem`].
+    fn read_habit(row: &Row) -> Result<HabitItem> {
+        Ok(HabitItem {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            active: row.get(2)?,
+            frequency: row.get(3)?,
+            current_streak: row.get(4)?,
+            max_streak: row.get(5)?,
+            weekly_target: row.get(6)?,
+            monthly_target: row.get(7)?,
+            yearly_target: row.get(8)?,
+            daily_target: row.get::<_, Option<u32>>(9)?.unwrap_or(1).max(1),
+            quit: row.get::<_, Option<bool>>(10)?.unwrap_or(false),
+            quit_since: row.get(11)?,
+            monthly_freezes: row.get::<_, Option<u32>>(12)?.unwrap_or(0),
+            challenge: row
+                .get::<_, Option<String>>(13)?
+                .and_then(|start| parse_date(&start))
+                .map(|start| -> Result<Challenge> {
+                    Ok(Challenge {
+                        start,
+                        end: row
+                            .get::<_, Option<String>>(14)?
+                            .and_then(|end| parse_date(&end)),
+                        target: row.get(15)?,
+                    })
+                })
+                .transpose()?,
+            challenge_result: row
+                .get::<_, Option<String>>(16)?
+                .and_then(|result| Outcome::parse(&result)),
+            category: row.get(17)?,
+            tags: row
+                .get::<_, Option<String>>(18)?
+                .map(|tags| tags.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
     }
+
     pub fn add_habit(&self, name: &str, frequency: &u32) -> Result<(HabitItem)> {
         self.conn.as_ref().unwrap().execute(
             "
-          INSERT INTO habits(name, active, frequency, current_streak, max_streak) 
-          VALUES (?1, ?2, ?3, ?4, ?5)",
+          INSERT INTO habits(name, active, frequency, current_streak, max_streak, position) 
+          VALUES (?1, ?2, ?3, ?4, ?5, (SELECT COALESCE(MAX(position), 0) + 1 FROM habits))",
             (name, true, frequency, 0, 0),
         )?; // this is a new habit. 
-        let mut stmt = self
-            .conn
-            .as_ref()
-            .unwrap()
-            .prepare("SELECT * FROM habits where name = (?1)")?;
-        stmt.execute([name])?;
-        let habit = stmt.query_row([], |row| {
-            Ok(HabitItem {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                active: row.get(2)?,
-                frequency: row.get(3)?,
-                current_streak: row.get(4)?,
-                max_streak: row.get(5)?,
-            })
-        })?;
+        let mut stmt = self.conn.as_ref().unwrap().prepare(&format!(
+            "SELECT {HABIT_COLUMNS} FROM habits where name = (?1)"
+        ))?;
+        let habit = stmt.query_row([name], Self::read_habit)?;
 
         return Ok(habit);
     }
 
+    /// Every habit in the saved order. Streaks are left for the model to work out
+    /// from the dates it loads anyway.
     pub fn get_habits(&self) -> Vec<HabitItem> {
         let mut stmt = self
             .conn
             .as_ref()
             .expect("should be a connection")
-            .prepare("SELECT * FROM habits")
+            .prepare(&format!(
+                "SELECT {HABIT_COLUMNS} FROM habits ORDER BY position, habit_id"
+            ))
             .expect("idk");
         let habit_vec: Vec<HabitItem> = stmt
-            .query_map([], |row| {
-                let id: u64 = row.get(0)?;
-                let frequency: u32 = row.get(3)?;
-                let computed_streak = self.compute_streak(id, frequency);
-
-                Ok(HabitItem {
-                    id,
-                    name: row.get(1)?,
-                    active: row.get(2)?,
-                    frequency,
-                    current_streak: computed_streak,
-                    max_streak: row.get(5)?,
-                })
-            })
+            .query_map([], Self::read_habit)
             .unwrap()
             .filter_map(|res| res.ok())
             .collect();
@@ -90,16 +319,471 @@ impl db {
         habit_vec
     }
 
-    pub fn add_completed(&self, date: &Date, item: &HabitItem, hours: u32) {
-        let _res = self.conn.as_ref().unwrap().execute(
-            "INSERT INTO habit_calendar(habit_id, date_completed, hours) 
-         VALUES (?1, ?2, ?3)
-         ON CONFLICT(habit_id, date_completed) 
-         DO UPDATE SET hours = ?3",
-            (item.id, date.to_string(), hours),
+    /// One habit by id, read like [`db::get_habits`].
+    pub fn get_habit(&self, habit_id: u64) -> Option<HabitItem> {
+        self.conn
+            .as_ref()
+            .expect("Connection refused")
+            .query_row(
+                &format!("SELECT {HABIT_COLUMNS} FROM habits WHERE habit_id = ?1"),
+                [habit_id],
+                Self::read_habit,
+            )
+            .ok()
+    }
+
+    /// Rows inserted, updated or deleted since the connection was opened; a
+    /// change means the data drawn on screen may be out of date.
+    pub fn total_changes(&self) -> u64 {
+        self.conn.as_ref().map_or(0, |conn| conn.total_changes())
+    }
+
+    pub fn get_setting(&self, key: &str) -> Option<String> {
+        self.conn
+            .as_ref()
+            .expect("Connection refused")
+            .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .ok()
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<usize> {
+        self.conn.as_ref().expect("Connection refused").execute(
+            "INSERT INTO settings(key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            (key, value),
+        )
+    }
+
+    /// Sets the weekly, monthly and yearly hour targets; `None` clears a target.
+    pub fn set_targets(&self, habit_id: u64, targets: [Option<f32>; 3]) -> Result<usize> {
+        self.conn.as_ref().expect("Connection refused").execute(
+            "UPDATE habits SET weekly_target = ?1, monthly_target = ?2, yearly_target = ?3
+             WHERE habit_id = ?4",
+            (targets[0], targets[1], targets[2], habit_id),
+        )
+    }
+
+    /// Turns a habit into a quit habit, counting clean days from today, or back.
+    pub fn set_quit(&self, habit_id: u64, quit: bool) -> Result<usize> {
+        self.conn.as_ref().expect("Connection refused").execute(
+            "UPDATE habits SET quit = ?1, quit_since = CASE WHEN ?1 THEN date('now') END
+             WHERE habit_id = ?2",
+            (quit, habit_id),
+        )
+    }
+
+    /// Swaps two habits in the manual order.
+    pub fn swap_positions(&self, first: u64, second: u64) -> Result<usize> {
+        let conn = self.conn.as_ref().expect("Connection refused");
+        let position = |habit_id: u64| {
+            conn.query_row(
+                "SELECT position FROM habits WHERE habit_id = ?1",
+                [habit_id],
+                |row| row.get::<_, i64>(0),
+            )
+        };
+        let (first_position, second_position) = (position(first)?, position(second)?);
+        conn.execute(
+            "UPDATE habits SET position = CASE habit_id WHEN ?1 THEN ?4 ELSE ?3 END
+             WHERE habit_id IN (?1, ?2)",
+            (first, second, first_position, second_position),
+        )
+    }
+
+    /// Starts a challenge, reactivating the habit if it was archived, or removes it.
+    pub fn set_challenge(&self, habit_id: u64, challenge: Option<&Challenge>) -> Result<usize> {
+        self.conn.as_ref().expect("Connection refused").execute(
+            "UPDATE habits SET challenge_start = ?1, challenge_end = ?2, challenge_target = ?3,
+                 challenge_result = NULL, active = 1
+             WHERE habit_id = ?4",
+            (
+                challenge.map(|c| c.start.to_string()),
+                challenge.and_then(|c| c.end.map(|end| end.to_string())),
+                challenge.and_then(|c| c.target),
+                habit_id,
+            ),
+        )
+    }
+
+    /// Full days completed from `start` to `end`, inclusive.
+    pub fn count_completed_between(&self, habit_id: u64, start: &Date, end: &Date) -> u32 {
+        self.conn
+            .as_ref()
+            .expect("Connection refused")
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM (
+                         SELECT date_completed FROM habit_calendar
+                         WHERE habit_id = ?1 AND date_completed BETWEEN ?2 AND ?3
+                         GROUP BY date_completed {FULL_DAYS}
+                     )"
+                ),
+                (habit_id, start.to_string(), end.to_string()),
+                |row| row.get(0),
+            )
+            .unwrap_or(0)
+    }
+
+    /// Records the outcome of every challenge that has been won or has run out
+    /// and archives its habit. Returns the names and outcomes of those closed.
+    pub fn close_finished_challenges(&self) -> Vec<(String, Outcome)> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(&format!(
+                "SELECT {HABIT_COLUMNS} FROM habits
+                 WHERE active AND challenge_start IS NOT NULL AND challenge_result IS NULL"
+            ))
+            .expect("wrong sql prep");
+        let running: Vec<HabitItem> = stmt
+            .query_map([], Self::read_habit)
+            .unwrap()
+            .filter_map(|res| res.ok())
+            .collect();
+        let today = time::OffsetDateTime::now_utc().date();
+        let mut closed = Vec::new();
+        for habit in running {
+            let Some(challenge) = &habit.challenge else {
+                continue;
+            };
+            let done = self.count_completed_between(
+                habit.id,
+                &challenge.start,
+                &challenge.last_day(today),
+            );
+            let Some(outcome) = challenge.outcome(done, habit.frequency, today) else {
+                continue;
+            };
+            let archived = self.conn.as_ref().expect("Connection refused").execute(
+                "UPDATE habits SET challenge_result = ?1, active = 0 WHERE habit_id = ?2",
+                (outcome.as_str(), habit.id),
+            );
+            if archived.is_ok() {
+                closed.push((habit.name, outcome));
+            }
+        }
+        closed
+    }
+
+    /// Replaces the habit's category and tags.
+    pub fn set_labels(&self, habit_id: u64, category: Option<&str>, tags: &[&str]) -> Result<()> {
+        let conn = self.conn.as_ref().expect("Connection refused");
+        conn.execute(
+            "UPDATE habits SET category = ?1 WHERE habit_id = ?2",
+            (category, habit_id),
+        )?;
+        conn.execute("DELETE FROM habit_tags WHERE habit_id = ?1", [habit_id])?;
+        for tag in tags {
+            conn.execute(
+                "INSERT OR IGNORE INTO habit_tags(habit_id, tag) VALUES (?1, ?2)",
+                (habit_id, tag),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Every category and tag in use, sorted.
+    pub fn list_labels(&self) -> Vec<String> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(
+                "SELECT category FROM habits WHERE category IS NOT NULL
+                 UNION SELECT tag FROM habit_tags
+                 ORDER BY 1",
+            )
+            .expect("wrong sql prep");
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .filter_map(|res| res.ok())
+            .collect()
+    }
+
+    /// Logs a completion. Counter habits get a new entry each time; other habits
+    /// keep one entry per day and have the first one's hours replaced, and its notes too
+    /// unless `notes` is empty.
+    pub fn add_completed(&self, date: &Date, item: &HabitItem, hours: f32, notes: &str) {
+        let conn = self.conn.as_ref().unwrap();
+        if !item.is_counter() {
+            let updated = conn.execute(
+                "UPDATE habit_calendar SET hours = ?3, logged_at = datetime('now'),
+                     notes = COALESCE(NULLIF(?4, ''), notes)
+                 WHERE entry_id = (SELECT MIN(entry_id) FROM habit_calendar
+                     WHERE habit_id = ?1 AND date_completed = ?2)",
+                (item.id, date.to_string(), hours, notes),
+            );
+            if let Ok(1..) = updated {
+                return;
+            }
+        }
+        let _res = conn.execute(
+            "INSERT INTO habit_calendar(habit_id, date_completed, hours, logged_at, notes)
+             VALUES (?1, ?2, ?3, datetime('now'), NULLIF(?4, ''))",
+            (item.id, date.to_string(), hours, notes),
         );
     }
 
+    /// Adds a timed session to the day's first entry, or logs it as a new one.
+    pub fn add_hours(&self, date: &Date, item: &HabitItem, hours: f32) {
+        if !item.is_counter() {
+            let updated = self.conn.as_ref().unwrap().execute(
+                "UPDATE habit_calendar SET hours = COALESCE(hours, 0) + ?3,
+                     logged_at = datetime('now')
+                 WHERE entry_id = (SELECT MIN(entry_id) FROM habit_calendar
+                     WHERE habit_id = ?1 AND date_completed = ?2)",
+                (item.id, date.to_string(), hours),
+            );
+            if let Ok(1..) = updated {
+                return;
+            }
+        }
+        self.add_completed(date, item, hours, "");
+    }
+
+    /// Completions whose notes match `text`, best matches first when full-text
+    /// search is available.
+    pub fn search_notes(&self, text: &str) -> Vec<NoteMatch> {
+        let conn = self.conn.as_ref().expect("Connection refused");
+        let (sql, pattern) = if Self::has_table(conn, "notes_fts").unwrap_or(false) {
+            (
+                "SELECT c.habit_id, h.name, c.date_completed, c.notes
+                 FROM notes_fts JOIN habit_calendar c ON c.entry_id = notes_fts.rowid
+                 JOIN habits h ON h.habit_id = c.habit_id
+                 WHERE notes_fts MATCH ?1 ORDER BY rank LIMIT 50",
+                search::fts_query(text),
+            )
+        } else {
+            (
+                "SELECT c.habit_id, h.name, c.date_completed, c.notes
+                 FROM habit_calendar c JOIN habits h ON h.habit_id = c.habit_id
+                 WHERE c.notes LIKE ?1 ORDER BY c.date_completed DESC LIMIT 50",
+                format!("%{}%", text.trim()),
+            )
+        };
+        let mut stmt = conn.prepare(sql).expect("wrong sql prep");
+        stmt.query_map([pattern], |row| {
+            Ok((
+                row.get::<_, u64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .unwrap()
+        .filter_map(|res| res.ok())
+        .filter_map(|(habit_id, habit_name, date, notes)| {
+            Some(NoteMatch {
+                habit_id,
+                habit_name,
+                date: parse_date(&date)?,
+                notes,
+            })
+        })
+        .collect()
+    }
+
+    /// Marks each habit done on `date`, logging entries up to its daily target,
+    /// or clears that day's blank entries: those without hours, notes or metric
+    /// values, as this adds them. Logged sessions are never deleted. Runs in one
+    /// transaction and returns how many habits changed.
+    pub fn set_done_batch(&self, habits: &[HabitItem], date: &Date, done: bool) -> Result<usize> {
+        let tx = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .unchecked_transaction()?;
+        let mut changed = 0;
+        for habit in habits {
+            let logged: u32 = tx.query_row(
+                "SELECT COUNT(*) FROM habit_calendar WHERE habit_id = ?1 AND date_completed = ?2",
+                (habit.id, date.to_string()),
+                |row| row.get(0),
+            )?;
+            if done && logged < habit.daily_target {
+                for _ in logged..habit.daily_target {
+                    tx.execute(
+                        "INSERT INTO habit_calendar(habit_id, date_completed, hours, logged_at)
+                         VALUES (?1, ?2, 0, datetime('now'))",
+                        (habit.id, date.to_string()),
+                    )?;
+                }
+                changed += 1;
+            } else if !done && logged > 0 {
+                let removed = tx.execute(
+                    "DELETE FROM habit_calendar WHERE habit_id = ?1 AND date_completed = ?2
+                     AND COALESCE(hours, 0) = 0 AND COALESCE(notes, '') = ''
+                     AND entry_id NOT IN (SELECT entry_id FROM metric_values)",
+                    (habit.id, date.to_string()),
+                )?;
+                if removed > 0 {
+                    changed += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// Sets how many entries a day count as done; above 1 makes it a counter habit.
+    pub fn set_daily_target(&self, habit_id: u64, daily_target: u32) -> Result<usize> {
+        self.conn.as_ref().expect("Connection refused").execute(
+            "UPDATE habits SET daily_target = ?1 WHERE habit_id = ?2",
+            (daily_target.max(1), habit_id),
+        )
+    }
+
+    /// Number of entries that logged hours, over the habit's whole history.
+    pub fn count_sessions(&self, habit_id: u64) -> u32 {
+        self.conn
+            .as_ref()
+            .expect("Connection refused")
+            .query_row(
+                "SELECT COUNT(*) FROM habit_calendar WHERE habit_id = ?1 AND hours > 0",
+                [habit_id],
+                |row| row.get(0),
+            )
+            .unwrap_or(0)
+    }
+
+    /// Number of entries logged for the habit on `date`.
+    pub fn count_entries(&self, habit_id: u64, date: &Date) -> u32 {
+        self.conn
+            .as_ref()
+            .expect("Connection refused")
+            .query_row(
+                "SELECT COUNT(*) FROM habit_calendar WHERE habit_id = ?1 AND date_completed = ?2",
+                (habit_id, date.to_string()),
+                |row| row.get(0),
+            )
+            .unwrap_or(0)
+    }
+
+    pub fn add_metric(
+        &self,
+        habit_id: u64,
+        name: &str,
+        unit: &str,
+        aggregate: Aggregate,
+    ) -> Result<usize> {
+        let aggregate = match aggregate {
+            Aggregate::Sum => "sum",
+            Aggregate::Average => "avg",
+        };
+        self.conn.as_ref().expect("Connection refused").execute(
+            "INSERT INTO habit_metrics(habit_id, name, unit, aggregate) VALUES (?1, ?2, ?3, ?4)",
+            (habit_id, name, unit, aggregate),
+        )
+    }
+
+    pub fn list_metrics(&self, habit_id: u64) -> Vec<HabitMetric> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(
+                "SELECT metric_id, name, unit, aggregate FROM habit_metrics
+                 WHERE habit_id = (?1) ORDER BY metric_id",
+            )
+            .expect("wrong sql prep");
+        stmt.query_map([habit_id], |row| {
+            let aggregate: String = row.get(3)?;
+            Ok(HabitMetric {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                unit: row.get(2)?,
+                aggregate: if aggregate == "avg" {
+                    Aggregate::Average
+                } else {
+                    Aggregate::Sum
+                },
+            })
+        })
+        .unwrap()
+        .filter_map(|res| res.ok())
+        .collect()
+    }
+
+    /// Logs a metric value with a completion on `date`: a new entry for counter
+    /// habits, the day's entry for others. Every value is kept, so two runs in a
+    /// day record both distances.
+    pub fn add_metric_value(
+        &self,
+        metric_id: u64,
+        item: &HabitItem,
+        date: &Date,
+        value: f64,
+    ) -> Result<()> {
+        let tx = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .unchecked_transaction()?;
+        let entry_id: u64 = if item.is_counter() {
+            tx.execute(
+                "INSERT INTO habit_calendar(habit_id, date_completed, hours, logged_at)
+                 VALUES (?1, ?2, 0, datetime('now'))",
+                (item.id, date.to_string()),
+            )?;
+            tx.last_insert_rowid() as u64
+        } else {
+            tx.execute(
+                "INSERT INTO habit_calendar(habit_id, date_completed, hours, logged_at)
+                 SELECT ?1, ?2, 0, datetime('now')
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM habit_calendar WHERE habit_id = ?1 AND date_completed = ?2
+                 )",
+                (item.id, date.to_string()),
+            )?;
+            tx.query_row(
+                "SELECT MIN(entry_id) FROM habit_calendar WHERE habit_id = ?1 AND date_completed = ?2",
+                (item.id, date.to_string()),
+                |row| row.get(0),
+            )?
+        };
+        tx.execute(
+            "INSERT INTO metric_values(metric_id, entry_id, value) VALUES (?1, ?2, ?3)",
+            (metric_id, entry_id, value),
+        )?;
+        tx.commit()
+    }
+
+    /// The metric per day, oldest first: the day's values summed, or averaged for
+    /// averaged metrics.
+    pub fn get_metric_values(&self, metric: &HabitMetric) -> Vec<(Date, f64)> {
+        let aggregate = match metric.aggregate {
+            Aggregate::Sum => "SUM",
+            Aggregate::Average => "AVG",
+        };
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(&format!(
+                "SELECT c.date_completed, {aggregate}(v.value) FROM metric_values v
+                 JOIN habit_calendar c ON c.entry_id = v.entry_id
+                 WHERE v.metric_id = (?1) GROUP BY c.date_completed ORDER BY c.date_completed"
+            ))
+            .expect("wrong sql prep");
+        stmt.query_map([metric.id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+        })
+        .unwrap()
+        .filter_map(|res| res.ok())
+        .filter_map(|(date, value)| {
+            let date = Date::parse(
+                &date,
+                &time::format_description::well_known::Iso8601::DEFAULT,
+            )
+            .ok()?;
+            Some((date, value))
+        })
+        .collect()
+    }
+
     pub fn get_id_from_name(&self, name: String) -> u64 {
         let mut stmt = self
             .conn
@@ -116,152 +800,235 @@ impl db {
         return id[0];
     }
 
-    pub fn list_completed_dates(&self, id: u64) -> Vec<String> {
+    pub fn list_completed_dates(&self, id: u64) -> Vec<Date> {
         let mut stmt = self
             .conn
             .as_ref()
             .expect("Connection refused")
-            .prepare("SELECT date_completed from habit_calendar WHERE habit_id = (?1)")
+            .prepare(&format!(
+                "SELECT date_completed from habit_calendar WHERE habit_id = (?1)
+                 GROUP BY date_completed {FULL_DAYS}"
+            ))
             .expect("wrong sql prep");
-        let dates_vec: Vec<String> = stmt
-            .query_map([id], |row| Ok(row.get(0)?))
+        let dates_vec: Vec<Date> = stmt
+            .query_map([id], |row| row.get::<_, String>(0))
             .unwrap()
             .filter_map(|res| res.ok())
+            .filter_map(|date| parse_date(&date))
             .collect();
         dates_vec
     }
 
-    pub fn list_streak_dates(&self, id: u64, frequency: u32, current_streak: u32) -> Vec<String> {
-        // If streak is <= 5, return empty vec (no orange highlighting)
-        if current_streak <= 5 {
-            return Vec::new();
-        }
-
-        // Get all completed dates ordered descending
+    /// Days a counter habit was logged on but fell short of its daily target.
+    pub fn list_partial_dates(&self, id: u64) -> Vec<Date> {
         let mut stmt = self
             .conn
             .as_ref()
             .expect("Connection refused")
-            .prepare("SELECT date_completed FROM habit_calendar WHERE habit_id = (?1) ORDER BY date_completed DESC")
+            .prepare(
+                "SELECT date_completed FROM habit_calendar WHERE habit_id = (?1)
+                 GROUP BY date_completed
+                 HAVING COUNT(*) < (SELECT COALESCE(daily_target, 1) FROM habits WHERE habit_id = (?1))",
+            )
             .expect("wrong sql prep");
+        stmt.query_map([id], |row| row.get::<_, String>(0))
+            .unwrap()
+            .filter_map(|res| res.ok())
+            .filter_map(|date| parse_date(&date))
+            .collect()
+    }
 
-        let dates_vec: Vec<String> = stmt
-            .query_map([id], |row| Ok(row.get(0)?))
+    /// Days a quit habit slipped, i.e. had anything logged, oldest first. Entries
+    /// from before it became a quit habit are not slips.
+    pub fn list_slip_dates(&self, id: u64) -> Vec<Date> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(
+                "SELECT DISTINCT date_completed FROM habit_calendar WHERE habit_id = (?1)
+                 AND date_completed >= COALESCE(
+                     (SELECT quit_since FROM habits WHERE habit_id = (?1)), ''
+                 ) ORDER BY date_completed",
+            )
+            .expect("wrong sql prep");
+        stmt.query_map([id], |row| row.get::<_, String>(0))
             .unwrap()
             .filter_map(|res| res.ok())
-            .collect();
+            .filter_map(|date| parse_date(&date))
+            .collect()
+    }
 
-        // Parse and filter to get only the dates that are part of the current streak
-        let parsed_dates: Vec<Date> = dates_vec
-            .iter()
-            .filter_map(|date_str| {
-                Date::parse(
-                    date_str,
-                    &time::format_description::well_known::Iso8601::DEFAULT,
-                )
-                .ok()
+    pub fn list_calendar_entries(&self, id: u64) -> Vec<habit_calendar> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(
+                "SELECT habit_id, date_completed, hours, entry_id, logged_at, notes FROM habit_calendar
+                 WHERE habit_id = (?1) ORDER BY date_completed, logged_at",
+            )
+            .expect("wrong sql prep");
+        stmt.query_map([id], |row| {
+            let hours: Option<f32> = row.get(2)?;
+            Ok(habit_calendar {
+                id: row.get(0)?,
+                date_completed: row.get(1)?,
+                hours: hours.unwrap_or(0.0),
+                notes: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                entry_id: row.get(3)?,
+                logged_at: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
             })
-            .collect();
+        })
+        .unwrap()
+        .filter_map(|res| res.ok())
+        .collect()
+    }
 
-        if parsed_dates.is_empty() {
-            return Vec::new();
-        }
+    pub fn list_daily_hours(&self, id: u64) -> Vec<(Date, f64)> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(
+                "SELECT date_completed, SUM(hours) FROM habit_calendar
+                 WHERE habit_id = (?1) GROUP BY date_completed",
+            )
+            .expect("wrong sql prep");
+        stmt.query_map([id], |row| {
+            let hours: Option<f64> = row.get(1)?;
+            Ok((row.get::<_, String>(0)?, hours.unwrap_or(0.0)))
+        })
+        .unwrap()
+        .filter_map(|res| res.ok())
+        .filter_map(|(date, hours)| Some((parse_date(&date)?, hours)))
+        .collect()
+    }
 
-        let today = time::OffsetDateTime::now_utc().date();
-        let mut streak_dates = Vec::new();
-        let mut expected_date = today;
-
-        // Only include dates that are part of the active streak
-        for completed_date in parsed_dates {
-            let gap = (expected_date - completed_date).whole_days();
-
-            if gap >= 0 && gap <= frequency as i64 {
-                streak_dates.push(completed_date.to_string());
-                expected_date = completed_date - time::Duration::days(frequency as i64);
-            } else {
-                break;
-            }
+    /// Skips `date` for the habit, or un-skips it if already skipped. Returns
+    /// whether the day is now skipped.
+    pub fn toggle_skip(&self, habit_id: u64, date: &Date) -> Result<bool> {
+        let conn = self.conn.as_ref().expect("Connection refused");
+        let removed = conn.execute(
+            "DELETE FROM skipped_days WHERE habit_id = ?1 AND date_skipped = ?2",
+            (habit_id, date.to_string()),
+        )?;
+        if removed > 0 {
+            return Ok(false);
         }
-
-        streak_dates
+        conn.execute(
+            "INSERT INTO skipped_days(habit_id, date_skipped) VALUES (?1, ?2)",
+            (habit_id, date.to_string()),
+        )?;
+        Ok(true)
     }
-    pub fn compute_streak(&self, habit_id: u64, frequency: u32) -> u32 {
-        // Get all completed dates for this habit, ordered by date descending
+
+    pub fn list_skipped_dates(&self, id: u64) -> Vec<Date> {
         let mut stmt = self
             .conn
             .as_ref()
             .expect("Connection refused")
-            .prepare("SELECT date_completed FROM habit_calendar WHERE habit_id = (?1) ORDER BY date_completed DESC")
+            .prepare("SELECT date_skipped FROM skipped_days WHERE habit_id = (?1)")
             .expect("wrong sql prep");
-
-        let dates_vec: Vec<String> = stmt
-            .query_map([habit_id], |row| Ok(row.get(0)?))
+        stmt.query_map([id], |row| row.get::<_, String>(0))
             .unwrap()
             .filter_map(|res| res.ok())
-            .collect();
-
-        if dates_vec.is_empty() {
-            return 0;
-        }
-
-        // Parse dates and calculate streak
-        let parsed_dates: Vec<Date> = dates_vec
-            .iter()
-            .filter_map(|date_str| {
-                Date::parse(
-                    date_str,
-                    &time::format_description::well_known::Iso8601::DEFAULT,
-                )
-                .ok()
-            })
-            .collect();
+            .filter_map(|date| parse_date(&date))
+            .collect()
+    }
 
-        if parsed_dates.is_empty() {
-            return 0;
+    /// Replaces the current and upcoming vacation with `range`, or ends it when
+    /// `None`. Past vacations are kept so old streaks stay intact.
+    pub fn set_vacation(&self, range: Option<(Date, Date)>) -> Result<()> {
+        let conn = self.conn.as_ref().expect("Connection refused");
+        conn.execute("DELETE FROM vacations WHERE start_date >= date('now')", [])?;
+        conn.execute(
+            "UPDATE vacations SET end_date = date('now', '-1 day') WHERE end_date >= date('now')",
+            [],
+        )?;
+        if let Some((start, end)) = range {
+            conn.execute(
+                "INSERT INTO vacations(start_date, end_date) VALUES (?1, ?2)",
+                (start.to_string(), end.to_string()),
+            )?;
         }
+        Ok(())
+    }
 
-        let today = time::OffsetDateTime::now_utc().date();
-        let mut streak = 0u32;
-        let mut expected_date = today;
-
-        // Check if the most recent completion is within the frequency window
-        let days_since_last = (today - parsed_dates[0]).whole_days();
-        if days_since_last > frequency as i64 {
-            // Streak is broken
-            return 0;
-        }
+    /// The vacation in progress or the next one planned.
+    pub fn upcoming_vacation(&self) -> Option<(String, String)> {
+        self.conn
+            .as_ref()
+            .expect("Connection refused")
+            .query_row(
+                "SELECT start_date, end_date FROM vacations WHERE end_date >= date('now')
+                 ORDER BY start_date LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok()
+    }
 
-        // Count consecutive completions within frequency windows
-        for completed_date in parsed_dates {
-            let gap = (expected_date - completed_date).whole_days();
+    /// Every day covered by a vacation; these pause all habits.
+    pub fn list_vacation_dates(&self) -> Vec<Date> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare("SELECT start_date, end_date FROM vacations")
+            .expect("wrong sql prep");
+        let parse = |date: String| {
+            Date::parse(
+                &date,
+                &time::format_description::well_known::Iso8601::DEFAULT,
+            )
+            .ok()
+        };
+        stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .unwrap()
+        .filter_map(|res| res.ok())
+        .filter_map(|(start, end)| Some((parse(start)?, parse(end)?)))
+        .flat_map(|(start, end)| streak::days_between(start, end))
+        .collect()
+    }
 
-            if gap >= 0 && gap <= frequency as i64 {
-                streak += 1;
-                expected_date = completed_date - time::Duration::days(frequency as i64);
-            } else {
-                break;
-            }
-        }
-        streak
+    /// Sets how many missed days a month may be frozen to keep a streak alive.
+    pub fn set_monthly_freezes(&self, habit_id: u64, freezes: u32) -> Result<usize> {
+        self.conn.as_ref().expect("Connection refused").execute(
+            "UPDATE habits SET monthly_freezes = ?1 WHERE habit_id = ?2",
+            (freezes, habit_id),
+        )
     }
 
-    pub fn get_hours(&self, habit_id: u64, tf: TimeFrame) -> u32 {
-        // takes in a time frame and returns habit hours for that timeframe
-        let date_str;
-        match tf {
-            TimeFrame::Month => date_str = "%Y-%m",
-            TimeFrame::Week => {
-                date_str = "%Y-%W";
-            }
-            TimeFrame::Year => {
-                date_str = "%Y";
-            }
-        }
-        let mut stmt = self.conn.as_ref().expect("Connection Refused").prepare("select SUM(hours) from habit_calendar where strftime((?1), date_completed) = strftime((?1), 'now') AND habit_id=(?2);").expect("wrong sql get date stuff");
-        let hours: Option<u32> = stmt
-            .query_row((date_str, habit_id), |row| row.get(0))
-            .unwrap_or(None);
-        hours.unwrap_or(0)
+    /// Per-day hours and completion counts over the habit's whole history, oldest first.
+    pub fn get_all_daily_totals(&self, habit_id: u64) -> Vec<(Date, f64, u32)> {
+        let mut stmt = self
+            .conn
+            .as_ref()
+            .expect("Connection refused")
+            .prepare(
+                "SELECT date_completed, SUM(hours), COUNT(*) FROM habit_calendar
+                 WHERE habit_id = (?1) GROUP BY date_completed ORDER BY date_completed",
+            )
+            .expect("wrong sql prep");
+        stmt.query_map([habit_id], |row| {
+            let date: String = row.get(0)?;
+            let hours: Option<f64> = row.get(1)?;
+            Ok((date, hours.unwrap_or(0.0), row.get(2)?))
+        })
+        .unwrap()
+        .filter_map(|res| res.ok())
+        .filter_map(|(date, hours, count)| {
+            let date = Date::parse(
+                &date,
+                &time::format_description::well_known::Iso8601::DEFAULT,
+            )
+            .ok()?;
+            Some((date, hours, count))
+        })
+        .collect()
     }
 
     pub fn delete_habit(&self, habit_id: u64) -> rusqlite::Result<usize> {
@@ -272,7 +1039,26 @@ impl db {
             )
         })?;
 
-        let rows_affected = conn.execute("DELETE FROM habits WHERE habit_id = (?1)", [habit_id])?;
+        // habit ids are reused, so nothing of this habit may be left for the next one
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM metric_values WHERE metric_id IN
+                 (SELECT metric_id FROM habit_metrics WHERE habit_id = ?1)",
+            [habit_id],
+        )?;
+        for table in [
+            "habit_metrics",
+            "habit_calendar",
+            "habit_tags",
+            "skipped_days",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE habit_id = ?1"),
+                [habit_id],
+            )?;
+        }
+        let rows_affected = tx.execute("DELETE FROM habits WHERE habit_id = (?1)", [habit_id])?;
+        tx.commit()?;
 
         Ok(rows_affected)
     }
@@ -283,3 +1069,103 @@ impl Default for db {
         db::new()
     }
 }
+
+fn parse_date(date: &str) -> Option<Date> {
+    Date::parse(
+        date,
+        &time::format_description::well_known::Iso8601::DEFAULT,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory() -> db {
+        let conn = Connection::open_in_memory().unwrap();
+        db::migrate(&conn).unwrap();
+        db { conn: Ok(conn) }
+    }
+
+    fn day() -> Date {
+        Date::from_calendar_date(2026, time::Month::March, 31).unwrap()
+    }
+
+    /// A habit whose day already holds `entries` blank entries, then set back to
+    /// one a day as if it had been a counter habit.
+    fn habit_with_entries(db: &db, entries: u32) -> HabitItem {
+        let mut habit = db.add_habit("read", &1).unwrap();
+        habit.daily_target = entries;
+        db.set_done_batch(std::slice::from_ref(&habit), &day(), true)
+            .unwrap();
+        db.set_daily_target(habit.id, 1).unwrap();
+        habit.daily_target = 1;
+        habit
+    }
+
+    fn day_total(db: &db, habit: &HabitItem) -> (f64, u32) {
+        let totals = db.get_all_daily_totals(habit.id);
+        assert_eq!(totals.len(), 1);
+        (totals[0].1, totals[0].2)
+    }
+
+    #[test]
+    fn logging_hours_replaces_only_the_first_entry() {
+        let db = memory();
+        let habit = habit_with_entries(&db, 3);
+        db.add_completed(&day(), &habit, 2.0, "");
+        assert_eq!(day_total(&db, &habit), (2.0, 3));
+        db.add_completed(&day(), &habit, 1.5, "");
+        assert_eq!(day_total(&db, &habit), (1.5, 3));
+    }
+
+    #[test]
+    fn timed_sessions_add_to_only_the_first_entry() {
+        let db = memory();
+        let habit = habit_with_entries(&db, 3);
+        db.add_hours(&day(), &habit, 0.5);
+        db.add_hours(&day(), &habit, 0.25);
+        assert_eq!(day_total(&db, &habit), (0.75, 3));
+    }
+
+    #[test]
+    fn counter_habits_log_a_new_entry_each_time() {
+        let db = memory();
+        let mut habit = db.add_habit("water", &1).unwrap();
+        habit.daily_target = 3;
+        db.add_completed(&day(), &habit, 0.0, "");
+        db.add_hours(&day(), &habit, 0.5);
+        assert_eq!(day_total(&db, &habit), (0.5, 2));
+    }
+
+    #[test]
+    fn a_reused_habit_id_starts_empty() {
+        let db = memory();
+        let habit = db.add_habit("read", &1).unwrap();
+        db.add_completed(&day(), &habit, 1.0, "a chapter");
+        db.set_labels(habit.id, Some("mind"), &["books"]).unwrap();
+        db.add_metric(habit.id, "pages", "p", Aggregate::Sum)
+            .unwrap();
+        let metric = db.list_metrics(habit.id).remove(0);
+        db.add_metric_value(metric.id, &habit, &day(), 20.0)
+            .unwrap();
+        db.toggle_skip(habit.id, &day()).unwrap();
+
+        db.delete_habit(habit.id).unwrap();
+        let next = db.add_habit("write", &1).unwrap();
+        assert_eq!(next.id, habit.id);
+        assert!(next.tags.is_empty());
+        assert!(db.list_labels().is_empty());
+        assert!(db.list_metrics(next.id).is_empty());
+        assert!(db.list_completed_dates(next.id).is_empty());
+        assert!(db.list_skipped_dates(next.id).is_empty());
+        let values: u32 = db
+            .conn
+            .as_ref()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM metric_values", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(values, 0);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::dashboard::TodayStatus;
+
+/// Optional columns of the habit table; the name is always shown.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HabitColumn {
+    Streak,
+    WeekHours,
+    Sparkline,
+    Today,
+}
+
+impl HabitColumn {
+    pub const ALL: [HabitColumn; 4] = [
+        HabitColumn::Streak,
+        HabitColumn::WeekHours,
+        HabitColumn::Sparkline,
+        HabitColumn::Today,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            HabitColumn::Streak => "Streak",
+            HabitColumn::WeekHours => "Week",
+            HabitColumn::Sparkline => "7 days",
+            HabitColumn::Today => "Today",
+        }
+    }
+
+    /// Cells are at least as wide as their title.
+    pub fn width(&self) -> u16 {
+        match self {
+            HabitColumn::Streak => 7,
+            HabitColumn::WeekHours => 6,
+            HabitColumn::Sparkline => 7,
+            HabitColumn::Today => 5,
+        }
+    }
+
+    /// Name saved in the `hidden_columns` setting.
+    pub fn key(&self) -> &'static str {
+        match self {
+            HabitColumn::Streak => "streak",
+            HabitColumn::WeekHours => "hours",
+            HabitColumn::Sparkline => "sparkline",
+            HabitColumn::Today => "today",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        HabitColumn::ALL
+            .into_iter()
+            .find(|column| column.key() == key)
+    }
+}
+
+/// Marker for the today column: done, due, or blank when not due.
+pub fn today_marker(status: TodayStatus) -> &'static str {
+    match status {
+        TodayStatus::Done | TodayStatus::Clean => "✓",
+        TodayStatus::Due => "●",
+        TodayStatus::NotDue => " ",
+        TodayStatus::Slipped => "✗",
+    }
+}
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One character per value, scaled to the largest; days with nothing logged are dots.
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|value| {
+            if *value <= 0.0 {
+                '·'
+            } else {
+                let level = (value / max * (BARS.len() - 1) as f64).round() as usize;
+                BARS[level.min(BARS.len() - 1)]
+            }
+        })
+        .collect()
+}
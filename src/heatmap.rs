@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Widget},
+};
+use time::{Date, Duration, Month};
+
+use crate::my_colors::{self, HEAT_LEVELS};
+
+const WEEKDAY_LABELS: [&str; 7] = ["Mo", "", "We", "", "Fr", "", "Su"];
+const LABEL_WIDTH: u16 = 3;
+
+/// A contribution-graph style view of one year: a column per week, a row per
+/// weekday, each cell shaded by the value logged on that day.
+#[derive(Debug, Default)]
+pub struct YearHeatmap<'a> {
+    year: i32,
+    values: HashMap<Date, u32>,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> YearHeatmap<'a> {
+    pub fn new(year: i32, values: HashMap<Date, u32>) -> Self {
+        Self {
+            year,
+            values,
+            block: None,
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// "Less ■■■■■ More" legend for the heat levels.
+    pub fn legend() -> Line<'static> {
+        let mut spans = vec![Span::raw("Less ")];
+        spans.extend(HEAT_LEVELS.iter().map(|color| Span::raw("■").fg(*color)));
+        spans.push(Span::raw(" More"));
+        Line::from(spans)
+    }
+}
+
+impl Widget for YearHeatmap<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let inner = match &self.block {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.clone().render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        let Ok(jan_first) = Date::from_calendar_date(self.year, Month::January, 1) else {
+            return;
+        };
+        if inner.height < 8 || inner.width <= LABEL_WIDTH {
+            return;
+        }
+
+        // Weeks start on Monday, so the first column may begin in December.
+        let first_monday =
+            jan_first - Duration::days(jan_first.weekday().number_days_from_monday() as i64);
+        let weeks = 54u16;
+        let cell_width = if inner.width >= LABEL_WIDTH + weeks * 2 {
+            2
+        } else {
+            1
+        };
+        let visible_weeks = ((inner.width - LABEL_WIDTH) / cell_width).min(weeks);
+        let max = self.values.values().copied().max().unwrap_or(0);
+
+        for (row, label) in WEEKDAY_LABELS.iter().enumerate() {
+            buf.set_string(
+                inner.x,
+                inner.y + 1 + row as u16,
+                label,
+                my_colors::NORMAL_STYLE,
+            );
+        }
+
+        for week in 0..visible_weeks {
+            let x = inner.x + LABEL_WIDTH + week * cell_width;
+            let week_start = first_monday + Duration::weeks(week as i64);
+            for row in 0..7u16 {
+                let date = week_start + Duration::days(row as i64);
+                if date.year() != self.year {
+                    continue;
+                }
+                if date.day() == 1 && x + 3 <= inner.right() {
+                    let month: &str = &date.month().to_string()[..3];
+                    buf.set_string(x, inner.y, month, Style::new().italic());
+                }
+                let value = self.values.get(&date).copied().unwrap_or(0);
+                let glyph = if cell_width == 2 { "■ " } else { "■" };
+                buf.set_string(
+                    x,
+                    inner.y + 1 + row,
+                    glyph,
+                    Style::new().fg(my_colors::heat_level(value, max)),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use time::{Date, Duration, Month};
+
+use crate::db::TimeFrame;
+
+/// Values and how many samples were logged within one week or month.
+#[derive(Debug, Clone)]
+pub struct PeriodTotal {
+    pub start: Date,
+    pub value: f64,
+    pub count: u32,
+}
+
+/// Moves `date` to the first day of the month `delta` months away.
+pub fn add_months(date: Date, delta: i32) -> Date {
+    let months = date.year() * 12 + i32::from(u8::from(date.month())) - 1 + delta;
+    let month = Month::try_from((months.rem_euclid(12) + 1) as u8).expect("month in 1..=12");
+    Date::from_calendar_date(months.div_euclid(12), month, 1).expect("valid first of month")
+}
+
+/// First day of the week (Monday), month or year containing `date`.
+pub fn period_start(date: Date, tf: &TimeFrame) -> Date {
+    match tf {
+        TimeFrame::Week => date - Duration::days(date.weekday().number_days_from_monday() as i64),
+        TimeFrame::Month => add_months(date, 0),
+        TimeFrame::Year => {
+            Date::from_calendar_date(date.year(), Month::January, 1).expect("valid first of year")
+        }
+    }
+}
+
+/// Start of the period after the one beginning at `start`.
+pub fn next_period(start: Date, tf: &TimeFrame) -> Date {
+    match tf {
+        TimeFrame::Week => start + Duration::weeks(1),
+        TimeFrame::Month => add_months(start, 1),
+        TimeFrame::Year => add_months(start, 12),
+    }
+}
+
+/// The `count` periods ending `offset` periods before the one containing `today`, oldest first.
+pub fn period_starts(today: Date, tf: &TimeFrame, count: u32, offset: u32) -> Vec<Date> {
+    let latest = period_start(today, tf);
+    (0..count)
+        .rev()
+        .map(|back| {
+            let back = (back + offset) as i32;
+            match tf {
+                TimeFrame::Week => latest - Duration::weeks(back as i64),
+                TimeFrame::Month => add_months(latest, -back),
+                TimeFrame::Year => add_months(latest, -back * 12),
+            }
+        })
+        .collect()
+}
+
+/// Sums daily `(date, value, count)` rows into the periods beginning at `starts`.
+pub fn bucket(daily: &[(Date, f64, u32)], starts: &[Date], tf: &TimeFrame) -> Vec<PeriodTotal> {
+    starts
+        .iter()
+        .map(|start| {
+            let end = next_period(*start, tf);
+            let mut total = PeriodTotal {
+                start: *start,
+                value: 0.0,
+                count: 0,
+            };
+            for (date, value, count) in daily {
+                if date >= start && *date < end {
+                    total.value += value;
+                    total.count += count;
+                }
+            }
+            total
+        })
+        .collect()
+}
@@ -0,0 +1,281 @@
+use std::fs;
+use std::io;
+
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::user_habits::{HabitItem, habit_calendar};
+
+/// File the export is written to, next to the database.
+pub const EXPORT_PATH: &str = "habit-tracker.ics";
+/// Entries logged before timestamps were recorded only have a date, so their
+/// timed events are placed at this hour.
+const SESSION_START_HOUR: u8 = 9;
+const PRODID: &str = "-//terminal-habit-tracker//habit export//EN";
+
+/// Builds an iCalendar document with a VEVENT per completion and a recurring
+/// VTODO per active habit schedule. Quit habits have nothing scheduled.
+pub fn build_calendar(habits: &[(HabitItem, Vec<habit_calendar>)], now: OffsetDateTime) -> String {
+    let stamp = format_timestamp(now);
+    let today = now.date();
+    let mut lines: Vec<String> = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Habits".to_string(),
+    ];
+
+    for (habit, entries) in habits {
+        let mut last_completed: Option<Date> = None;
+        for entry in entries {
+            let Ok(date) = Date::parse(
+                &entry.date_completed,
+                &time::format_description::well_known::Iso8601::DEFAULT,
+            ) else {
+                continue;
+            };
+            last_completed = Some(date);
+            lines.extend(completion_event(habit, entry, date, &stamp));
+        }
+        if habit.active && !habit.quit {
+            lines.extend(schedule_todo(habit, last_completed, today, &stamp));
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+pub fn write_calendar(path: &str, contents: &str) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
+fn completion_event(
+    habit: &HabitItem,
+    entry: &habit_calendar,
+    date: Date,
+    stamp: &str,
+) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!(
+            "UID:habit-{}-{}-{}@terminal-habit-tracker",
+            entry.id,
+            format_date(date),
+            entry.entry_id
+        ),
+        format!("DTSTAMP:{stamp}"),
+        format!("SUMMARY:{}", escape_text(&habit.name)),
+    ];
+    let minutes = (entry.hours * 60.0).round() as i64;
+    if minutes > 0 {
+        match logged_time(&entry.logged_at) {
+            // the entry is logged when the session ends, so it started `minutes` earlier
+            Some(logged) => {
+                let start = (logged - minutes).max(0);
+                lines.push(format!(
+                    "DTSTART:{}T{:02}{:02}00Z",
+                    format_date(date),
+                    start / 60,
+                    start % 60
+                ));
+            }
+            None => lines.push(format!(
+                "DTSTART:{}T{:02}0000",
+                format_date(date),
+                SESSION_START_HOUR
+            )),
+        }
+        lines.push(format!("DURATION:PT{}H{}M", minutes / 60, minutes % 60));
+    } else {
+        lines.push(format!("DTSTART;VALUE=DATE:{}", format_date(date)));
+        lines.push(format!(
+            "DTEND;VALUE=DATE:{}",
+            format_date(date + Duration::days(1))
+        ));
+    }
+    if !entry.notes.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape_text(&entry.notes)));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines
+}
+
+fn schedule_todo(
+    habit: &HabitItem,
+    last_completed: Option<Date>,
+    today: Date,
+    stamp: &str,
+) -> Vec<String> {
+    // frequency is the number of days allowed between completions
+    let interval = habit.frequency.max(1);
+    let next_due = match last_completed {
+        Some(date) => (date + Duration::days(interval as i64)).max(today),
+        None => today,
+    };
+    vec![
+        "BEGIN:VTODO".to_string(),
+        format!("UID:habit-{}-schedule@terminal-habit-tracker", habit.id),
+        format!("DTSTAMP:{stamp}"),
+        format!("SUMMARY:{}", escape_text(&habit.name)),
+        format!("DTSTART;VALUE=DATE:{}", format_date(next_due)),
+        format!("DUE;VALUE=DATE:{}", format_date(next_due)),
+        format!("RRULE:FREQ=DAILY;INTERVAL={interval}"),
+        "END:VTODO".to_string(),
+    ]
+}
+
+/// Minutes after midnight (UTC) from a "YYYY-MM-DD HH:MM:SS" timestamp.
+fn logged_time(logged_at: &str) -> Option<i64> {
+    let hour: i64 = logged_at.get(11..13)?.parse().ok()?;
+    let minute: i64 = logged_at.get(14..16)?.parse().ok()?;
+    Some(hour * 60 + minute)
+}
+
+fn format_date(date: Date) -> String {
+    format!(
+        "{:04}{:02}{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+fn format_timestamp(now: OffsetDateTime) -> String {
+    format!(
+        "{}T{:02}{:02}{:02}Z",
+        format_date(now.date()),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Content lines longer than 75 octets have to be folded (RFC 5545 3.1).
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date() -> Date {
+        Date::from_calendar_date(2026, time::Month::March, 31).unwrap()
+    }
+
+    fn entry(hours: f32, logged_at: &str, notes: &str) -> habit_calendar {
+        habit_calendar {
+            id: 7,
+            date_completed: date().to_string(),
+            hours,
+            notes: notes.to_string(),
+            entry_id: 42,
+            logged_at: logged_at.to_string(),
+        }
+    }
+
+    fn event(entry: &habit_calendar) -> Vec<String> {
+        let habit = HabitItem {
+            id: 7,
+            name: "Run, fast".to_string(),
+            ..HabitItem::default()
+        };
+        completion_event(&habit, entry, date(), "20260331T200000Z")
+    }
+
+    #[test]
+    fn short_lines_are_not_folded() {
+        assert_eq!(fold_line("SUMMARY:Run"), "SUMMARY:Run\r\n");
+        let exact = "x".repeat(75);
+        assert_eq!(fold_line(&exact), format!("{exact}\r\n"));
+    }
+
+    #[test]
+    fn long_lines_fold_at_75_octets() {
+        let line = "x".repeat(80);
+        assert_eq!(
+            fold_line(&line),
+            format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(5))
+        );
+    }
+
+    #[test]
+    fn folding_never_splits_a_character() {
+        let line = format!("DESCRIPTION:{}", "é🏃".repeat(30));
+        let folded = fold_line(&line);
+        for physical in folded.split("\r\n").filter(|part| !part.is_empty()) {
+            assert!(physical.len() <= 75, "{physical:?}");
+        }
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+    }
+
+    #[test]
+    fn text_values_are_escaped() {
+        assert_eq!(escape_text("a;b,c\\d\nnext"), r"a\;b\,c\\d\nnext");
+    }
+
+    #[test]
+    fn logged_time_reads_minutes_after_midnight() {
+        assert_eq!(logged_time("2026-03-31 14:05:09"), Some(14 * 60 + 5));
+        assert_eq!(logged_time("2026-03-31"), None);
+        assert_eq!(logged_time(""), None);
+    }
+
+    #[test]
+    fn timed_sessions_start_before_they_were_logged() {
+        let lines = event(&entry(0.5, "2026-03-31 14:05:00", ""));
+        assert!(lines.contains(&"DTSTART:20260331T133500Z".to_string()));
+        assert!(lines.contains(&"DURATION:PT0H30M".to_string()));
+        // a session logged just after midnight starts at midnight, not the day before
+        let lines = event(&entry(1.0, "2026-03-31 00:20:00", ""));
+        assert!(lines.contains(&"DTSTART:20260331T000000Z".to_string()));
+    }
+
+    #[test]
+    fn old_sessions_start_at_a_fixed_hour() {
+        let lines = event(&entry(1.25, "2026-03-31", ""));
+        assert!(lines.contains(&"DTSTART:20260331T090000".to_string()));
+        assert!(lines.contains(&"DURATION:PT1H15M".to_string()));
+    }
+
+    #[test]
+    fn untimed_completions_are_all_day() {
+        let lines = event(&entry(0.0, "2026-03-31 14:05:00", "felt good; 5k"));
+        assert_eq!(
+            lines,
+            [
+                "BEGIN:VEVENT",
+                "UID:habit-7-20260331-42@terminal-habit-tracker",
+                "DTSTAMP:20260331T200000Z",
+                r"SUMMARY:Run\, fast",
+                "DTSTART;VALUE=DATE:20260331",
+                "DTEND;VALUE=DATE:20260401",
+                r"DESCRIPTION:felt good\; 5k",
+                "END:VEVENT",
+            ]
+        );
+    }
+}
@@ -1,12 +1,54 @@
 use crossterm::event::KeyCode;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum InputMode {
     Normal,
     EnteringName,
     EnteringFrequency,
     MarkingDone,
     EnteringHours,
+    ViewingStats,
+    /// Editing a setting of the selected habit through the edit box.
+    EditingHabit(HabitField),
+    /// Typing in the search box: habit names are fuzzy matched, notes full-text searched.
+    Searching,
+    /// The today checklist: habits due today, checked off as they are done.
+    Checklist,
+    /// Picking several habits in the list to mark done together.
+    Selecting,
+}
+
+/// Habit settings that can be changed from Normal mode.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HabitField {
+    Targets,
+    Metric,
+    DailyTarget,
+    Freezes,
+    Challenge,
+    Labels,
+    /// Work and break lengths; saving starts a pomodoro on the habit.
+    Pomodoro,
+    /// A day to excuse the habit on, or to stop excusing.
+    Skip,
+    /// Not tied to the selected habit: a vacation pauses every habit.
+    Vacation,
+}
+
+impl HabitField {
+    pub fn title(&self) -> &'static str {
+        match self {
+            HabitField::Targets => "Hour targets (week/month/year, - for none)",
+            HabitField::Metric => "New metric: name unit [sum|avg]",
+            HabitField::DailyTarget => "Times per day (1 for a normal habit)",
+            HabitField::Freezes => "Streak freezes per month (0 for none)",
+            HabitField::Challenge => "Challenge: 30d, 20x and/or dates (off to end)",
+            HabitField::Labels => "category: tag tag (e.g. health: morning outdoor)",
+            HabitField::Pomodoro => "Pomodoro: work and break minutes (e.g. 25 5)",
+            HabitField::Skip => "Skip or unskip: date, or days ago (1 for yesterday)",
+            HabitField::Vacation => "Vacation: from to, or days from today (off to end)",
+        }
+    }
 }
 
 impl Default for InputMode {
@@ -21,7 +63,8 @@ impl InputMode {
             (InputMode::EnteringName, _) => *self = InputMode::EnteringFrequency,
             (InputMode::EnteringFrequency, _) => *self = InputMode::Normal,
             (InputMode::MarkingDone, _) => *self = InputMode::EnteringHours,
-            (InputMode::EnteringHours, _) => *self = InputMode::Normal,
+            (InputMode::EnteringHours, _) => *self = InputMode::ViewingStats,
+            (InputMode::ViewingStats, _) => *self = InputMode::Normal,
             (InputMode::Normal, KeyCode::Char('a')) => *self = InputMode::EnteringName,
             (InputMode::Normal, KeyCode::Tab) => *self = InputMode::MarkingDone,
             (_, _) => {}
@@ -31,7 +74,8 @@ impl InputMode {
         match self {
             InputMode::EnteringFrequency => *self = InputMode::EnteringName,
             InputMode::EnteringHours => *self = InputMode::MarkingDone,
+            InputMode::ViewingStats => *self = InputMode::EnteringHours,
             _ => *self = InputMode::Normal,
         }
     }
-}
\ No newline at end of file
+}
@@ -1,16 +1,35 @@
 use color_eyre::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use time::{Date, OffsetDateTime};
+use std::time::Instant;
+use time::{Date, Duration, OffsetDateTime};
 
 use crate::app::App;
-use crate::db::{self};
-use crate::input_mode::InputMode;
+use crate::calendar_view::CalendarView;
+use crate::challenge::Challenge;
+use crate::checklist::ChecklistItem;
+use crate::db::{self, TimeFrame};
+use crate::habit_table::HabitColumn;
+use crate::ics_export;
+use crate::input_mode::{HabitField, InputMode};
+use crate::model::Model;
+use crate::search;
+use crate::sort_mode::SortMode;
+use crate::timer::{Clock, Timer, TimerKind};
+use crate::user_habits::{Aggregate, HabitItem};
+/// How long to wait for input before redrawing anyway.
+pub const TICK_RATE: std::time::Duration = std::time::Duration::from_millis(250);
+
+const BAD_AMOUNT: &str = "Hours and amounts must be a number of 0 or more";
+
 impl App {
     /// Reads the crossterm events and updates the state of [`App`].
     ///
-    /// If your application needs to perform work in between handling events, you can use the
-    /// [`event::poll`] function to check if there are any events available with a timeout.
+    /// Waits at most [`TICK_RATE`] for an event, so the screen is redrawn and
+    /// [`App::on_tick`] runs even while no keys come in.
     pub fn handle_crossterm_events(&mut self) -> Result<()> {
+        if !event::poll(TICK_RATE)? {
+            return Ok(());
+        }
         match event::read()? {
             // it's important to check KeyEventKind::Press to avoid handling key release events
             Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
@@ -21,6 +40,29 @@ impl App {
         Ok(())
     }
 
+    /// Work done between events: noticing when the day changes.
+    pub fn on_tick(&mut self) {
+        let today = OffsetDateTime::now_utc().date();
+        if self.today == Some(today) {
+            return;
+        }
+        if let Some(yesterday) = self.today.replace(today) {
+            self.roll_over(yesterday, today);
+        }
+    }
+
+    /// Past midnight: streaks and today's checklist are recomputed, and views
+    /// that were showing the current year follow it into the new one.
+    fn roll_over(&mut self, yesterday: Date, today: Date) {
+        if self.heatmap_year == yesterday.year() {
+            self.heatmap_year = today.year();
+        }
+        self.close_finished_challenges();
+        self.reload();
+        self.checklist_state.select_first();
+        self.status = format!("New day: {today}");
+    }
+
     /// Handles the key events and updates the state of [`App`].
     fn on_key_event(&mut self, key: KeyEvent) {
         match self.input_mode {
@@ -29,7 +71,43 @@ impl App {
             InputMode::EnteringFrequency => self.handle_freq_input(key),
             InputMode::MarkingDone => self.handle_input_done(key),
             InputMode::EnteringHours => self.handle_input_done(key),
+            InputMode::ViewingStats => self.handle_stats_view(key),
+            InputMode::EditingHabit(field) => self.handle_edit_input(key, field),
+            InputMode::Searching => self.handle_search_input(key),
+            InputMode::Selecting => self.handle_selecting(key),
+            InputMode::Checklist => self.handle_checklist(key),
+        }
+        self.sync_model();
+    }
+
+    /// Brings the model up to date when the last event wrote to the database:
+    /// only the habit the handler touched is reread if nothing else changed.
+    fn sync_model(&mut self) {
+        let touched = self.touched.take();
+        if self.db.total_changes() == self.model.changes {
+            return;
+        }
+        // a completion may have won a challenge
+        let closed = self.close_finished_challenges();
+        match touched {
+            Some(habit_id) if !closed => self.model.refresh_habit(&self.db, habit_id),
+            _ => self.reload(),
+        }
+    }
+
+    /// Rereads every habit from the database.
+    pub fn reload(&mut self) {
+        self.model = Model::load(&self.db);
+    }
+
+    /// Archives challenges that were won or ran out; true if any were. Only
+    /// called from key handling, the tick and startup, never while drawing.
+    pub fn close_finished_challenges(&mut self) -> bool {
+        let closed = self.db.close_finished_challenges();
+        for (name, outcome) in &closed {
+            self.status = format!("{name} challenge {outcome}, archived");
         }
+        !closed.is_empty()
     }
 
     fn handle_normal_mode(&mut self, key: KeyEvent) {
@@ -41,7 +119,11 @@ impl App {
             (_, KeyCode::Char('k') | KeyCode::Up) => self.select_previous(),
             (_, KeyCode::Char('g') | KeyCode::Home) => self.select_first(),
             (_, KeyCode::Char('G') | KeyCode::End) => self.select_last(),
-            (_, KeyCode::Esc) => self.habits.show_add_habit = false,
+            (_, KeyCode::Esc) => {
+                self.habits.show_add_habit = false;
+                self.habits.show_dashboard = false;
+            }
+            (_, KeyCode::Char('o')) => self.habits.show_dashboard = !self.habits.show_dashboard,
             (_, KeyCode::Char('a')) => {
                 self.input_mode.next(key.code);
                 self.habits.show_add_habit = true;
@@ -54,12 +136,315 @@ impl App {
                         .expect("this habit id does not exist");
                 }
             }
+            (_, KeyCode::Char('e')) => self.export_calendar(),
+            (_, KeyCode::Char('T')) => self.start_editing(HabitField::Targets),
+            (_, KeyCode::Char('M')) => self.start_editing(HabitField::Metric),
+            (_, KeyCode::Char('C')) => self.start_editing(HabitField::DailyTarget),
+            (_, KeyCode::Char('B')) => self.toggle_quit(),
+            (_, KeyCode::Char('S')) => self.start_editing(HabitField::Skip),
+            (_, KeyCode::Char('F')) => self.start_editing(HabitField::Freezes),
+            (_, KeyCode::Char('V')) => self.start_editing(HabitField::Vacation),
+            (_, KeyCode::Char('c')) => self.start_editing(HabitField::Challenge),
+            (_, KeyCode::Char('A')) => self.habits.show_archived = !self.habits.show_archived,
+            (_, KeyCode::Char('#')) => self.start_editing(HabitField::Labels),
+            (_, KeyCode::Char('f')) => self.next_tag_filter(),
+            (_, KeyCode::Char('s')) => {
+                self.habits.sort_mode.next();
+                self.status = format!("Sorted by {}", self.habits.sort_mode);
+            }
+            (_, KeyCode::Char(digit @ '1'..='4')) => {
+                self.toggle_column(HabitColumn::ALL[digit as usize - '1' as usize])
+            }
+            (_, KeyCode::Char(' ')) => self.toggle_done_today(),
+            (_, KeyCode::Char('w')) if self.timer.is_some() => self.stop_timer(),
+            (_, KeyCode::Char('w')) => self.start_timer(TimerKind::Stopwatch),
+            (_, KeyCode::Char('P')) if self.timer.is_some() => self.stop_timer(),
+            (_, KeyCode::Char('P')) => self.start_editing(HabitField::Pomodoro),
+            (_, KeyCode::Char('t')) => {
+                self.checklist_state.select_first();
+                self.input_mode = InputMode::Checklist;
+            }
+            (_, KeyCode::Char('v')) => {
+                self.picked.clear();
+                self.input_mode = InputMode::Selecting;
+            }
+            (_, KeyCode::Char('J')) => self.move_habit(true),
+            (_, KeyCode::Char('K')) => self.move_habit(false),
+            (_, KeyCode::Char('/')) => {
+                self.search_selected = None;
+                self.search_hits.clear();
+                self.input_mode = InputMode::Searching;
+            }
             (_, KeyCode::Tab) => {
                 self.input_mode.next(key.code);
             }
             _ => {}
         }
     }
+
+    /// Switches the selected habit between a habit to build and one to quit.
+    fn toggle_quit(&mut self) {
+        let Some(idx) = self.habits.state.selected() else {
+            return;
+        };
+        let habit = &self.habits.items[idx];
+        let quit = !habit.quit;
+        self.touched = Some(habit.id);
+        self.status = match self.db.set_quit(habit.id, quit) {
+            Ok(_) if quit => format!("Quitting {}: log slips, clean days count", habit.name),
+            Ok(_) => format!("Building {} again", habit.name),
+            Err(e) => format!("Could not change habit type: {e}"),
+        };
+    }
+
+    /// Moves the selected habit one place down (or up) in the saved order, within
+    /// its category.
+    fn move_habit(&mut self, down: bool) {
+        if self.habits.sort_mode != SortMode::Manual {
+            self.status = "Press s until the list is in manual order to move habits".to_string();
+            return;
+        }
+        let Some(idx) = self.habits.state.selected() else {
+            return;
+        };
+        let Some(target) = (if down {
+            idx.checked_add(1)
+        } else {
+            idx.checked_sub(1)
+        }) else {
+            return;
+        };
+        let (Some(habit), Some(other)) =
+            (self.habits.items.get(idx), self.habits.items.get(target))
+        else {
+            return;
+        };
+        if habit.category != other.category {
+            return;
+        }
+        match self.db.swap_positions(habit.id, other.id) {
+            Ok(_) => self.habits.state.select(Some(target)),
+            Err(e) => self.status = format!("Could not move habit: {e}"),
+        }
+    }
+
+    /// Filters the habit list to the next tag or category, then back to all habits.
+    fn next_tag_filter(&mut self) {
+        let labels = self.db.list_labels();
+        let next = match &self.tag_filter {
+            None => 0,
+            Some(current) => labels
+                .iter()
+                .position(|label| label == current)
+                .map_or(labels.len(), |i| i + 1),
+        };
+        self.tag_filter = labels.get(next).cloned();
+        self.habits.state.select(None);
+    }
+
+    /// Marks the selected habit done today, or undoes today's entries if it already is.
+    fn toggle_done_today(&mut self) {
+        let Some(idx) = self.habits.state.selected() else {
+            return;
+        };
+        let habit = self.habits.items[idx].clone();
+        if habit.quit {
+            self.status = format!(
+                "{} is a quit habit; log slips from the calendar",
+                habit.name
+            );
+            return;
+        }
+        let today = OffsetDateTime::now_utc().date();
+        let done = self.db.count_entries(habit.id, &today) < habit.daily_target;
+        self.touched = Some(habit.id);
+        self.status = match self
+            .db
+            .set_done_batch(std::slice::from_ref(&habit), &today, done)
+        {
+            Ok(_) if done => format!("{} done today", habit.name),
+            Ok(_) => self.undone_status(&habit, &today),
+            Err(e) => format!("Could not update {}: {e}", habit.name),
+        };
+    }
+
+    /// After undoing a habit for `date`: entries with hours, notes or metric
+    /// values are kept, so say why the day may still show as logged.
+    fn undone_status(&self, habit: &HabitItem, date: &Date) -> String {
+        if self.db.count_entries(habit.id, date) == 0 {
+            format!("{} no longer done today", habit.name)
+        } else {
+            format!("{} keeps today's logged hours and notes", habit.name)
+        }
+    }
+
+    /// Space checks the selected habit off (or unchecks it); typing fills the hours
+    /// box, which Enter logs. Esc clears the box, then leaves for the habit list.
+    /// Letters always go to the box, so a note may start with any of them; quit
+    /// from the habit list.
+    fn handle_checklist(&mut self, key: KeyEvent) {
+        let typing = !self.habit_hours_buffer.content.is_empty();
+        match (key.modifiers, key.code) {
+            (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
+            (_, KeyCode::Esc) if typing => self.habit_hours_buffer.handle_key(KeyCode::Esc),
+            (_, KeyCode::Esc) => self.input_mode = InputMode::Normal,
+            (_, KeyCode::Down) => self.checklist_state.select_next(),
+            (_, KeyCode::Up) => self.checklist_state.select_previous(),
+            (_, KeyCode::Char(' ') | KeyCode::Enter) if !typing => {
+                let Some(item) = self.selected_checklist_item() else {
+                    return;
+                };
+                self.touched = Some(item.habit.id);
+                let today = OffsetDateTime::now_utc().date();
+                if !item.done {
+                    self.db.add_completed(&today, &item.habit, 0.0, "");
+                    self.status = format!("{} done", item.habit.name);
+                } else if key.code == KeyCode::Char(' ') {
+                    self.status = match self.db.set_done_batch(
+                        std::slice::from_ref(&item.habit),
+                        &today,
+                        false,
+                    ) {
+                        Ok(_) => self.undone_status(&item.habit, &today),
+                        Err(e) => format!("Could not uncheck {}: {e}", item.habit.name),
+                    };
+                }
+            }
+            (_, KeyCode::Enter) => {
+                let Some(item) = self.selected_checklist_item() else {
+                    return;
+                };
+                let Some((hours, notes)) = parse_log(&self.habit_hours_buffer.content) else {
+                    self.status = BAD_AMOUNT.to_string();
+                    return;
+                };
+                self.touched = Some(item.habit.id);
+                let today = OffsetDateTime::now_utc().date();
+                self.db.add_completed(&today, &item.habit, hours, notes);
+                self.status = format!("Logged {hours}h of {}", item.habit.name);
+                self.habit_hours_buffer.handle_key(KeyCode::Esc);
+            }
+            (_, code) => self.habit_hours_buffer.handle_key(code),
+        }
+    }
+
+    fn selected_checklist_item(&self) -> Option<ChecklistItem> {
+        let idx = self.checklist_state.selected()?;
+        self.today_checklist().into_iter().nth(idx)
+    }
+
+    /// Space picks or unpicks the selected habit, Enter marks every picked habit
+    /// (or the selected one if none are picked) done today.
+    fn handle_selecting(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
+            KeyCode::Char(' ') => {
+                if let Some(id) = self.get_current_habit() {
+                    match self.picked.iter().position(|picked| *picked == id) {
+                        Some(i) => {
+                            self.picked.remove(i);
+                        }
+                        None => self.picked.push(id),
+                    }
+                }
+            }
+            KeyCode::Char('a') => {
+                self.picked = self.habits.items.iter().map(|habit| habit.id).collect();
+            }
+            KeyCode::Enter => {
+                if self.picked.is_empty() {
+                    self.picked.extend(self.get_current_habit());
+                }
+                let habits: Vec<HabitItem> = self
+                    .habits
+                    .items
+                    .iter()
+                    .filter(|habit| !habit.quit && self.picked.contains(&habit.id))
+                    .cloned()
+                    .collect();
+                let today = OffsetDateTime::now_utc().date();
+                self.status = match self.db.set_done_batch(&habits, &today, true) {
+                    Ok(count) => format!("Marked {count} habits done today"),
+                    Err(e) => format!("Could not mark habits done: {e}"),
+                };
+                self.picked.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.picked.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    fn start_timer(&mut self, kind: TimerKind) {
+        let Some(idx) = self.habits.state.selected() else {
+            return;
+        };
+        let habit = self.habits.items[idx].clone();
+        if habit.quit {
+            self.status = format!("{} is a quit habit; there is nothing to time", habit.name);
+            return;
+        }
+        self.status = format!("Timing {}", habit.name);
+        self.timer = Some(Timer::start(habit, kind));
+    }
+
+    /// Stops the timer and logs the time worked as hours today.
+    fn stop_timer(&mut self) {
+        let Some(timer) = self.timer.take() else {
+            return;
+        };
+        let worked = timer.worked(Instant::now());
+        if worked.as_secs() < 60 {
+            self.status = "Under a minute, nothing logged".to_string();
+            return;
+        }
+        let today = OffsetDateTime::now_utc().date();
+        self.db
+            .add_hours(&today, &timer.habit, worked.as_secs_f32() / 3600.0);
+        self.touched = Some(timer.habit.id);
+        self.status = format!("Logged {} of {}", Clock(worked), timer.habit.name);
+    }
+
+    /// Shows or hides a habit table column and remembers the choice.
+    fn toggle_column(&mut self, column: HabitColumn) {
+        let hidden = &mut self.habits.hidden_columns;
+        let shown = if let Some(i) = hidden.iter().position(|c| *c == column) {
+            hidden.remove(i);
+            true
+        } else {
+            hidden.push(column);
+            false
+        };
+        let keys: Vec<&str> = hidden.iter().map(|c| c.key()).collect();
+        self.status = match self.db.set_setting("hidden_columns", &keys.join(",")) {
+            Ok(_) if shown => format!("Showing the {} column", column.title()),
+            Ok(_) => format!("Hid the {} column", column.title()),
+            Err(e) => format!("Could not save columns: {e}"),
+        };
+    }
+
+    /// Writes every habit's completions and schedule to an `.ics` file.
+    fn export_calendar(&mut self) {
+        let habits: Vec<_> = self
+            .model
+            .habits
+            .iter()
+            .cloned()
+            .map(|habit| {
+                let entries = self.db.list_calendar_entries(habit.id);
+                (habit, entries)
+            })
+            .collect();
+        let contents = ics_export::build_calendar(&habits, OffsetDateTime::now_utc());
+        self.status = match ics_export::write_calendar(ics_export::EXPORT_PATH, &contents) {
+            Ok(()) => format!("Exported to {}", ics_export::EXPORT_PATH),
+            Err(e) => format!("Export failed: {e}"),
+        };
+    }
     fn handle_input_done(&mut self, key: KeyEvent) {
         if (self.input_mode == InputMode::EnteringHours) {
             self.habit_hours_buffer.handle_key(key.code);
@@ -68,25 +453,71 @@ impl App {
             KeyCode::BackTab => {
                 self.input_mode.prev();
             }
-            KeyCode::Left => {
+            KeyCode::Char('v') if self.input_mode == InputMode::MarkingDone => {
+                self.habits.calendar_view.next();
+            }
+            KeyCode::Char('c') if self.input_mode == InputMode::MarkingDone => {
+                self.heatmap_by_hours = !self.heatmap_by_hours;
+            }
+            KeyCode::Left if self.input_mode == InputMode::MarkingDone => {
                 //display previous months calendar
-                todo!()
+                if self.habits.calendar_view == CalendarView::YearHeatmap {
+                    self.heatmap_year -= 1;
+                } else {
+                    self.calendar_month_offset += 1;
+                }
+            }
+            KeyCode::Right if self.input_mode == InputMode::MarkingDone => {
+                if self.habits.calendar_view == CalendarView::YearHeatmap {
+                    self.heatmap_year += 1;
+                } else {
+                    self.calendar_month_offset -= 1;
+                }
             }
-            KeyCode::Right => {
-                todo!()
+            KeyCode::Up | KeyCode::Down if self.input_mode == InputMode::EnteringHours => {
+                let metrics = self
+                    .get_current_habit()
+                    .map_or(0, |id| self.db.list_metrics(id).len());
+                self.log_metric = if key.code == KeyCode::Down {
+                    (self.log_metric + 1) % (metrics + 1)
+                } else {
+                    (self.log_metric + metrics) % (metrics + 1)
+                };
             }
             KeyCode::Enter => {
                 let idx = self.habits.state.selected();
-                if idx.is_some() {
-                    self.db.add_completed(
-                        &OffsetDateTime::now_utc().date(),
-                        &self.habits.items[idx.unwrap()],
-                        self.habit_hours_buffer.content.parse().unwrap_or(0),
-                    );
+                let metric = self.log_metric.checked_sub(1).and_then(|i| {
+                    let id = self.get_current_habit()?;
+                    self.db.list_metrics(id).into_iter().nth(i)
+                });
+                let today = OffsetDateTime::now_utc().date();
+                self.touched = self.get_current_habit();
+                if let (Some(idx), Some(metric)) = (idx, metric) {
+                    // a metric value also counts as completing the habit that day
+                    match self.habit_hours_buffer.content.trim().parse::<f64>() {
+                        Ok(value) if is_amount(value) => {
+                            let habit = &self.habits.items[idx];
+                            if let Err(e) =
+                                self.db.add_metric_value(metric.id, habit, &today, value)
+                            {
+                                self.status = format!("Could not log {}: {e}", metric.name);
+                            }
+                        }
+                        _ => {
+                            self.status = BAD_AMOUNT.to_string();
+                            return;
+                        }
+                    }
+                } else if idx.is_some() {
+                    let Some((hours, notes)) = parse_log(&self.habit_hours_buffer.content) else {
+                        self.status = BAD_AMOUNT.to_string();
+                        return;
+                    };
+                    self.db
+                        .add_completed(&today, &self.habits.items[idx.unwrap()], hours, notes);
                 }
                 self.habit_hours_buffer.content.clear();
                 self.habit_hours_buffer.cursor_position = 0;
-                
             }
             KeyCode::Tab => {
                 self.input_mode.next(key.code);
@@ -95,6 +526,270 @@ impl App {
             _ => {}
         }
     }
+    /// Typing jumps the selection to the best matching habit; Up/Down pick a
+    /// matching note and Enter opens its day in the calendar.
+    fn handle_search_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.search_buffer.handle_key(KeyCode::Esc);
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Down | KeyCode::Up => {
+                let hits = self.search_hits.len();
+                if hits == 0 {
+                    return;
+                }
+                self.search_selected = Some(match (self.search_selected, key.code) {
+                    (None, KeyCode::Down) => 0,
+                    (None, _) => hits - 1,
+                    (Some(i), KeyCode::Down) => (i + 1) % hits,
+                    (Some(i), _) => (i + hits - 1) % hits,
+                });
+            }
+            KeyCode::Enter => {
+                if let Some(hit) = self
+                    .search_selected
+                    .and_then(|i| self.search_hits.get(i).cloned())
+                {
+                    self.open_note(hit.habit_id, hit.date);
+                }
+                self.search_buffer.handle_key(KeyCode::Esc);
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {
+                self.search_buffer.handle_key(key.code);
+                self.search_selected = None;
+                let query = self.search_buffer.content.trim();
+                self.search_hits = if query.is_empty() {
+                    Vec::new()
+                } else {
+                    self.db.search_notes(query)
+                };
+                if let Some(idx) =
+                    search::best_match(query, self.habits.items.iter().map(|h| h.name.as_str()))
+                {
+                    self.habits.state.select(Some(idx));
+                }
+            }
+        }
+    }
+
+    /// Selects the habit, showing it even if filtered out or archived, and turns
+    /// the calendar to the month of `date`.
+    fn open_note(&mut self, habit_id: u64, date: Date) {
+        if !self.habits.items.iter().any(|habit| habit.id == habit_id) {
+            self.tag_filter = None;
+            self.habits.show_archived = true;
+            self.load_habits();
+        }
+        let idx = self
+            .habits
+            .items
+            .iter()
+            .position(|habit| habit.id == habit_id);
+        self.habits.state.select(idx);
+        let today = OffsetDateTime::now_utc().date();
+        let months = |d: Date| d.year() * 12 + i32::from(u8::from(d.month()));
+        self.calendar_month_offset = months(today) - months(date);
+        self.heatmap_year = date.year();
+        self.status = format!("Notes from {date}");
+    }
+
+    /// Opens the edit box for `field` on the selected habit, prefilled with its current value.
+    fn start_editing(&mut self, field: HabitField) {
+        let habit = match self.habits.state.selected() {
+            Some(idx) => self.habits.items[idx].clone(),
+            None if field == HabitField::Vacation => HabitItem::default(),
+            None => return,
+        };
+        let content = match field {
+            HabitField::Targets => {
+                let targets = [
+                    habit.weekly_target,
+                    habit.monthly_target,
+                    habit.yearly_target,
+                ];
+                if targets.iter().all(Option::is_none) {
+                    String::new()
+                } else {
+                    targets
+                        .iter()
+                        .map(|target| target.map_or("-".to_string(), |t| t.to_string()))
+                        .collect::<Vec<String>>()
+                        .join("/")
+                }
+            }
+            HabitField::Metric => String::new(),
+            HabitField::DailyTarget => habit.daily_target.to_string(),
+            HabitField::Freezes => habit.monthly_freezes.to_string(),
+            HabitField::Labels => match &habit.category {
+                Some(category) => format!("{category}: {}", habit.tags.join(" ")),
+                None => habit.tags.join(" "),
+            },
+            HabitField::Challenge => habit
+                .challenge
+                .as_ref()
+                .map(|challenge| challenge.to_string())
+                .unwrap_or_default(),
+            HabitField::Pomodoro => self
+                .db
+                .get_setting("pomodoro")
+                .unwrap_or_else(|| "25 5".to_string()),
+            HabitField::Skip => OffsetDateTime::now_utc().date().to_string(),
+            HabitField::Vacation => self
+                .db
+                .upcoming_vacation()
+                .map(|(start, end)| format!("{start} {end}"))
+                .unwrap_or_default(),
+        };
+        self.habit_edit_buffer.cursor_position = content.len();
+        self.habit_edit_buffer.content = content;
+        self.input_mode = InputMode::EditingHabit(field);
+    }
+
+    fn handle_edit_input(&mut self, key: KeyEvent, field: HabitField) {
+        match key.code {
+            KeyCode::Enter => {
+                self.save_edit(field);
+                self.habit_edit_buffer.handle_key(KeyCode::Esc);
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.habit_edit_buffer.handle_key(key.code);
+                self.input_mode = InputMode::Normal;
+            }
+            _ => self.habit_edit_buffer.handle_key(key.code),
+        }
+    }
+
+    fn save_edit(&mut self, field: HabitField) {
+        let content = self.habit_edit_buffer.content.trim().to_string();
+        if field == HabitField::Vacation {
+            let today = OffsetDateTime::now_utc().date();
+            self.status = match parse_vacation(&content, today) {
+                Some(range) => match self.db.set_vacation(range) {
+                    Ok(()) => match range {
+                        Some((start, end)) => format!("On vacation from {start} to {end}"),
+                        None => "Vacation ended".to_string(),
+                    },
+                    Err(e) => format!("Could not save vacation: {e}"),
+                },
+                None => "Vacations look like 2026-07-01 2026-07-14, or 7 for a week".to_string(),
+            };
+            return;
+        }
+        let Some(habit_id) = self.get_current_habit() else {
+            return;
+        };
+        self.touched = Some(habit_id);
+        self.status = match field {
+            HabitField::Targets => match parse_targets(&content) {
+                Some(targets) => match self.db.set_targets(habit_id, targets) {
+                    Ok(_) => "Targets saved".to_string(),
+                    Err(e) => format!("Could not save targets: {e}"),
+                },
+                None => {
+                    "Targets look like 5/20/250, use - to leave one out, none below 0".to_string()
+                }
+            },
+            HabitField::Metric => match parse_metric(&content) {
+                Some((name, unit, aggregate)) => {
+                    match self.db.add_metric(habit_id, name, unit, aggregate) {
+                        Ok(_) => format!("Tracking {name} in {unit}"),
+                        Err(e) => format!("Could not add metric: {e}"),
+                    }
+                }
+                None => "Metrics look like: distance km, or pace min/km avg".to_string(),
+            },
+            HabitField::DailyTarget => match content.parse::<u32>() {
+                Ok(target) if target > 0 => match self.db.set_daily_target(habit_id, target) {
+                    Ok(_) => format!("Done after {target} per day"),
+                    Err(e) => format!("Could not save daily target: {e}"),
+                },
+                _ => "Times per day must be a whole number above 0".to_string(),
+            },
+            HabitField::Freezes => match content.parse::<u32>() {
+                Ok(freezes) => match self.db.set_monthly_freezes(habit_id, freezes) {
+                    Ok(_) => format!("{freezes} streak freezes a month"),
+                    Err(e) => format!("Could not save streak freezes: {e}"),
+                },
+                Err(_) => "Streak freezes must be a whole number".to_string(),
+            },
+            HabitField::Challenge => {
+                let today = OffsetDateTime::now_utc().date();
+                let challenge = if content.eq_ignore_ascii_case("off") {
+                    Some(None)
+                } else {
+                    Challenge::parse(&content, today).map(Some)
+                };
+                match challenge {
+                    Some(challenge) => match self.db.set_challenge(habit_id, challenge.as_ref()) {
+                        Ok(_) if challenge.is_some() => "Challenge started".to_string(),
+                        Ok(_) => "Challenge removed".to_string(),
+                        Err(e) => format!("Could not save challenge: {e}"),
+                    },
+                    None => "Challenges look like 30d, 20x, 2026-11-01 2026-11-30 or 30d 20x"
+                        .to_string(),
+                }
+            }
+            HabitField::Labels => {
+                let (category, tags) = parse_labels(&content);
+                match self.db.set_labels(habit_id, category, &tags) {
+                    Ok(()) => "Tags saved".to_string(),
+                    Err(e) => format!("Could not save tags: {e}"),
+                }
+            }
+            HabitField::Pomodoro => match TimerKind::parse_pomodoro(&content) {
+                Some(kind) => {
+                    if let Err(e) = self.db.set_setting("pomodoro", &content) {
+                        self.status = format!("Could not save pomodoro lengths: {e}");
+                    }
+                    self.start_timer(kind);
+                    return;
+                }
+                None => "Pomodoros look like 25 5: minutes of work, then of break".to_string(),
+            },
+            HabitField::Skip => {
+                let today = OffsetDateTime::now_utc().date();
+                let name = self.model.habit(habit_id).map_or("", |habit| &habit.name);
+                match parse_skip(&content, today) {
+                    Some(date) => match self.db.toggle_skip(habit_id, &date) {
+                        Ok(true) => format!("Skipping {name} on {date}"),
+                        Ok(false) => format!("{name} is no longer skipped on {date}"),
+                        Err(e) => format!("Could not skip: {e}"),
+                    },
+                    None => "Skips look like 2026-07-01, or 1 for yesterday".to_string(),
+                }
+            }
+            HabitField::Vacation => unreachable!("vacations are saved above"),
+        };
+    }
+
+    fn handle_stats_view(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Left => self.stats_offset += 1,
+            KeyCode::Right => self.stats_offset = self.stats_offset.saturating_sub(1),
+            KeyCode::Char('w') => {
+                self.stats_timeframe = TimeFrame::Week;
+                self.stats_offset = 0;
+            }
+            KeyCode::Char('m') => {
+                self.stats_timeframe = TimeFrame::Month;
+                self.stats_offset = 0;
+            }
+            KeyCode::Char('c') => {
+                let metrics = self
+                    .get_current_habit()
+                    .map_or(0, |id| self.db.list_metrics(id).len());
+                self.stats_value.next(metrics);
+            }
+            KeyCode::Char('d') => self.trend_weekly = !self.trend_weekly,
+            KeyCode::Char('v') => self.habits.stats_view.next(),
+            KeyCode::BackTab => self.input_mode.prev(),
+            KeyCode::Tab | KeyCode::Esc => self.input_mode = InputMode::Normal,
+            _ => {}
+        }
+    }
     fn handle_name_input(&mut self, key: KeyEvent) {
         self.habit_name_buffer.handle_key(key.code);
         match key.code {
@@ -153,10 +848,105 @@ impl App {
     fn select_last(&mut self) {
         self.habits.state.select_last();
     }
-    /// Set running to false to quit the application.
+    /// Exits, first logging a running timer so the session is not lost.
     fn quit(&mut self) {
         if !self.habits.show_add_habit {
+            self.stop_timer();
             self.running = false;
         }
     }
 }
+
+/// Parses "week/month/year" hour targets; blank or `-` leaves a target unset.
+fn parse_targets(content: &str) -> Option<[Option<f32>; 3]> {
+    let mut targets = [None; 3];
+    let parts: Vec<&str> = content.split('/').map(str::trim).collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    for (target, part) in targets.iter_mut().zip(parts) {
+        if !part.is_empty() && part != "-" {
+            *target = Some(
+                part.parse()
+                    .ok()
+                    .filter(|target: &f32| is_amount((*target).into()))?,
+            );
+        }
+    }
+    Some(targets)
+}
+
+/// Parses "name unit [sum|avg]" for a new metric.
+fn parse_metric(content: &str) -> Option<(&str, &str, Aggregate)> {
+    let parts: Vec<&str> = content.split_whitespace().collect();
+    let aggregate = match parts.get(2).copied() {
+        None | Some("sum") => Aggregate::Sum,
+        Some("avg") => Aggregate::Average,
+        Some(_) => return None,
+    };
+    match parts.as_slice() {
+        [name, unit] | [name, unit, _] => Some((name, unit, aggregate)),
+        _ => None,
+    }
+}
+
+/// Parses "2026-07-01 2026-07-14" or a number of days starting today; "off" (or
+/// nothing) ends the vacation, giving `Some(None)`.
+fn parse_vacation(content: &str, today: Date) -> Option<Option<(Date, Date)>> {
+    if content.is_empty() || content.eq_ignore_ascii_case("off") {
+        return Some(None);
+    }
+    if let Ok(days) = content.parse::<i64>() {
+        return (days > 0).then(|| Some((today, today + Duration::days(days - 1))));
+    }
+    let format = time::format_description::well_known::Iso8601::DEFAULT;
+    let (start, end) = content.split_once(char::is_whitespace)?;
+    let start = Date::parse(start.trim(), &format).ok()?;
+    let end = Date::parse(end.trim(), &format).ok()?;
+    (start <= end).then_some(Some((start, end)))
+}
+
+/// Reads the day to skip: an ISO date, or a number of days before `today`.
+fn parse_skip(content: &str, today: Date) -> Option<Date> {
+    if content.is_empty() {
+        return Some(today);
+    }
+    if let Ok(days) = content.parse::<u32>() {
+        return today.checked_sub(Duration::days(days.into()));
+    }
+    let format = time::format_description::well_known::Iso8601::DEFAULT;
+    Date::parse(content, &format).ok()
+}
+
+/// Splits "health: morning outdoor" into the category and tags; without a colon
+/// every word is a tag. A leading `#` on a tag is dropped.
+fn parse_labels(content: &str) -> (Option<&str>, Vec<&str>) {
+    let (category, tags) = match content.split_once(':') {
+        Some((category, tags)) => (Some(category.trim()).filter(|c| !c.is_empty()), tags),
+        None => (None, content),
+    };
+    let tags = tags
+        .split_whitespace()
+        .map(|tag| tag.trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    (category, tags)
+}
+
+/// Splits the log box into hours and a note: "1.5 long run" logs 1.5 hours with
+/// the note "long run"; text without leading hours is all note. `None` when the
+/// leading number is negative or not finite.
+fn parse_log(content: &str) -> Option<(f32, &str)> {
+    let content = content.trim();
+    let (first, rest) = content.split_once(' ').unwrap_or((content, ""));
+    match first.parse::<f32>() {
+        Ok(hours) if is_amount(hours.into()) => Some((hours, rest.trim())),
+        Ok(_) => None,
+        Err(_) => Some((0.0, content)),
+    }
+}
+
+/// Hours, targets and metric values are finite and never negative.
+fn is_amount(value: f64) -> bool {
+    value.is_finite() && value >= 0.0
+}
@@ -1,11 +1,27 @@
 mod app;
+mod calendar_view;
+mod challenge;
+mod checklist;
+mod dashboard;
 mod date_styler;
 mod my_colors;
 mod user_habits;
 mod db;
 mod key_handlers;
 mod text_input;
+mod timer;
 mod input_mode;
+mod stats;
+mod stats_view;
+mod trend;
+mod ics_export;
+mod habit_table;
+mod heatmap;
+mod history;
+mod model;
+mod search;
+mod sort_mode;
+mod streak;
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
     let terminal = ratatui::init();
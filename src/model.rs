@@ -0,0 +1,240 @@
+use std::collections::{HashMap, HashSet};
+
+use time::{Date, Duration, OffsetDateTime};
+
+use crate::date_styler::CompletedDateStyler;
+use crate::db::{TimeFrame, db};
+use crate::history;
+use crate::streak::{self, StreakWalk};
+use crate::user_habits::{HabitItem, HabitMetric};
+
+/// What the screen shows about one habit, read from the database when the
+/// habit changes instead of on every frame.
+#[derive(Debug, Default, Clone)]
+pub struct HabitData {
+    /// Hours and entries per day over the whole history, oldest first.
+    pub daily: Vec<(Date, f64, u32)>,
+    /// Entries that logged hours; a counter habit's day may hold several.
+    pub sessions: u32,
+    /// Days the habit was done, or slipped for a quit habit.
+    pub dates: Vec<Date>,
+    /// Latest of `dates` up to today.
+    pub last: Option<Date>,
+    /// First day a quit habit is counted as clean or slipped.
+    pub quit_since: Option<Date>,
+    pub styler: CompletedDateStyler,
+    /// Each metric with its values, oldest first.
+    pub metrics: Vec<(HabitMetric, Vec<(Date, f64)>)>,
+    /// Completions counting towards the habit's challenge.
+    pub challenge_done: u32,
+}
+
+impl HabitData {
+    /// Reads the habit's data and fills in its current and best streak from the
+    /// same dates, so undoing a completion also undoes the best streak it set.
+    /// For quit habits these are clean runs. `vacation_dates` pause all habits.
+    pub fn load(db: &db, habit: &mut HabitItem, vacation_dates: &[Date]) -> Self {
+        let today = OffsetDateTime::now_utc().date();
+        let (dates, styler, quit_since) = if habit.quit {
+            let slips = db.list_slip_dates(habit.id);
+            let quit_since = habit
+                .quit_since
+                .as_deref()
+                .and_then(|since| {
+                    Date::parse(
+                        since,
+                        &time::format_description::well_known::Iso8601::DEFAULT,
+                    )
+                    .ok()
+                })
+                .or_else(|| slips.first().copied());
+            let since = quit_since.unwrap_or(today);
+            habit.current_streak = streak::clean_streak(&slips, since, today);
+            habit.max_streak = streak::longest_clean(&slips, since, today);
+            let styler = quit_date_styler(&slips, habit.current_streak, today);
+            (slips, styler, quit_since)
+        } else {
+            let completed = db.list_completed_dates(habit.id);
+            let skipped = db.list_skipped_dates(habit.id);
+            let excused: HashSet<Date> = skipped.iter().chain(vacation_dates).copied().collect();
+            let walk = streak::walk(
+                &completed,
+                &excused,
+                habit.frequency,
+                habit.monthly_freezes,
+                today,
+            );
+            habit.current_streak = walk.current();
+            habit.max_streak = walk.longest;
+            let styler =
+                habit_date_styler(db, habit.id, &completed, skipped, &walk, vacation_dates);
+            (completed, styler, None)
+        };
+        HabitData {
+            daily: db.get_all_daily_totals(habit.id),
+            sessions: db.count_sessions(habit.id),
+            last: dates.iter().filter(|date| **date <= today).max().copied(),
+            quit_since,
+            dates,
+            styler,
+            metrics: db
+                .list_metrics(habit.id)
+                .into_iter()
+                .map(|metric| {
+                    let values = db.get_metric_values(&metric);
+                    (metric, values)
+                })
+                .collect(),
+            challenge_done: habit.challenge.as_ref().map_or(0, |challenge| {
+                db.count_completed_between(habit.id, &challenge.start, &challenge.last_day(today))
+            }),
+        }
+    }
+
+    /// Daily rows for dates in `[start, end)`.
+    pub fn between(&self, start: Date, end: Date) -> Vec<(Date, f64, u32)> {
+        let from = self.daily.partition_point(|(date, _, _)| *date < start);
+        let to = self.daily.partition_point(|(date, _, _)| *date < end);
+        self.daily[from..to.max(from)].to_vec()
+    }
+
+    /// One row per clean day of a quit habit in `[start, end)`: days from
+    /// [`HabitData::quit_since`] up to today without a slip.
+    pub fn clean_between(&self, start: Date, end: Date, today: Date) -> Vec<(Date, f64, u32)> {
+        let Some(since) = self.quit_since else {
+            return Vec::new();
+        };
+        let (from, to) = (start.max(since), end.min(today + Duration::days(1)));
+        if from >= to {
+            return Vec::new();
+        }
+        streak::days_between(from, to - Duration::days(1))
+            .into_iter()
+            // slip dates are sorted for quit habits
+            .filter(|day| self.dates.binary_search(day).is_err())
+            .map(|day| (day, 1.0, 1))
+            .collect()
+    }
+
+    /// Hours and entries logged on `date`.
+    pub fn on(&self, date: Date) -> (f64, u32) {
+        self.between(date, date + Duration::days(1))
+            .first()
+            .map_or((0.0, 0), |(_, hours, count)| (*hours, *count))
+    }
+
+    /// Hours logged in the week, month or year containing `today`.
+    pub fn hours(&self, tf: &TimeFrame, today: Date) -> f64 {
+        let start = history::period_start(today, tf);
+        self.between(start, history::next_period(start, tf))
+            .iter()
+            .fold(0.0, |total, (_, hours, _)| total + hours)
+    }
+}
+
+/// Every habit and the data drawn for it, loaded once and then kept up to date
+/// as the database changes so drawing a frame does no I/O.
+#[derive(Debug, Default)]
+pub struct Model {
+    /// All habits, archived ones included, in the saved order.
+    pub habits: Vec<HabitItem>,
+    /// Categories and tags in use.
+    pub labels: Vec<String>,
+    pub vacation_dates: Vec<Date>,
+    pub data: HashMap<u64, HabitData>,
+    /// [`db::total_changes`] when the model was last brought up to date.
+    pub changes: u64,
+    empty: HabitData,
+}
+
+impl Model {
+    pub fn load(db: &db) -> Self {
+        let vacation_dates = db.list_vacation_dates();
+        let mut habits = Vec::new();
+        let mut data = HashMap::new();
+        for mut habit in db.get_habits() {
+            data.insert(habit.id, HabitData::load(db, &mut habit, &vacation_dates));
+            habits.push(habit);
+        }
+        Model {
+            habits,
+            labels: db.list_labels(),
+            vacation_dates,
+            data,
+            changes: db.total_changes(),
+            empty: HabitData::default(),
+        }
+    }
+
+    /// Reloads one habit after only its own rows changed.
+    pub fn refresh_habit(&mut self, db: &db, habit_id: u64) {
+        match db.get_habit(habit_id) {
+            Some(mut habit) => {
+                self.data.insert(
+                    habit_id,
+                    HabitData::load(db, &mut habit, &self.vacation_dates),
+                );
+                if let Some(item) = self.habits.iter_mut().find(|item| item.id == habit_id) {
+                    *item = habit;
+                }
+            }
+            None => {
+                self.habits.retain(|item| item.id != habit_id);
+                self.data.remove(&habit_id);
+            }
+        }
+        // a habit's category or tags may have changed
+        self.labels = db.list_labels();
+        self.changes = db.total_changes();
+    }
+
+    pub fn habit(&self, habit_id: u64) -> Option<&HabitItem> {
+        self.habits.iter().find(|habit| habit.id == habit_id)
+    }
+
+    pub fn data(&self, habit_id: u64) -> &HabitData {
+        self.data.get(&habit_id).unwrap_or(&self.empty)
+    }
+}
+
+/// Styles the habit's completions, streak, logged hours, partial, skipped,
+/// vacation and frozen days.
+fn habit_date_styler(
+    db: &db,
+    habit_id: u64,
+    completed: &[Date],
+    skipped: Vec<Date>,
+    walk: &StreakWalk,
+    vacation_dates: &[Date],
+) -> CompletedDateStyler {
+    // only streaks past five days are highlighted
+    let streak_dates = if walk.current() > 5 {
+        walk.dates.clone()
+    } else {
+        Vec::new()
+    };
+    let mut date_styled_cal = CompletedDateStyler::new();
+    date_styled_cal.update_dates(completed.iter().copied());
+    date_styled_cal.update_streak_dates(streak_dates);
+    date_styled_cal.update_hours(db.list_daily_hours(habit_id));
+    date_styled_cal.update_partial_dates(db.list_partial_dates(habit_id));
+    date_styled_cal.update_skipped_dates(skipped);
+    date_styled_cal.vacation_dates = vacation_dates.iter().copied().collect();
+    date_styled_cal.frozen_dates = walk.frozen.iter().copied().collect();
+    date_styled_cal
+}
+
+/// Date styler for a quit habit: slips, and the clean run since the last one.
+fn quit_date_styler(slips: &[Date], clean: u32, today: Date) -> CompletedDateStyler {
+    let clean_dates = if clean > 5 {
+        (0..clean)
+            .map(|back| today - Duration::days(back.into()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut date_styled_cal = CompletedDateStyler::new();
+    date_styled_cal.update_slip_dates(slips.iter().copied());
+    date_styled_cal.update_streak_dates(clean_dates);
+    date_styled_cal
+}
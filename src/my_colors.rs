@@ -14,6 +14,49 @@ pub const STREAK_STYLE: Style = Style::new()
     .fg(convert_color_type(PALETTE.macchiato.colors.peach))
     .add_modifier(Modifier::BOLD);
 
+/// Counter habit days logged but short of the daily target.
+pub const PARTIAL_STYLE: Style = Style::new()
+    .fg(convert_color_type(PALETTE.macchiato.colors.yellow))
+    .add_modifier(Modifier::ITALIC);
+
+/// Days a quit habit slipped.
+pub const SLIP_STYLE: Style = Style::new()
+    .fg(convert_color_type(PALETTE.macchiato.colors.red))
+    .add_modifier(Modifier::REVERSED);
+
+/// Days excused from a streak: skipped, on vacation, or covered by a streak freeze.
+pub const SKIP_STYLE: Style = Style::new()
+    .fg(convert_color_type(PALETTE.macchiato.colors.overlay1))
+    .add_modifier(Modifier::ITALIC);
+pub const VACATION_STYLE: Style = Style::new()
+    .fg(convert_color_type(PALETTE.macchiato.colors.sky))
+    .add_modifier(Modifier::ITALIC);
+pub const FREEZE_STYLE: Style = Style::new()
+    .fg(convert_color_type(PALETTE.macchiato.colors.blue))
+    .add_modifier(Modifier::UNDERLINED);
+
+pub const SHORT_AVERAGE_COL: Color = convert_color_type(PALETTE.macchiato.colors.teal);
+pub const LONG_AVERAGE_COL: Color = convert_color_type(PALETTE.macchiato.colors.mauve);
+
+/// Heatmap cell colours from "nothing logged" up to the busiest days.
+pub const HEAT_LEVELS: [Color; 5] = [
+    convert_color_type(PALETTE.macchiato.colors.surface0),
+    convert_color_type(PALETTE.macchiato.colors.teal),
+    convert_color_type(PALETTE.macchiato.colors.green),
+    convert_color_type(PALETTE.macchiato.colors.yellow),
+    convert_color_type(PALETTE.macchiato.colors.peach),
+];
+
+/// Picks the heat level for `value` relative to the largest value shown.
+pub fn heat_level(value: u32, max: u32) -> Color {
+    if value == 0 || max == 0 {
+        return HEAT_LEVELS[0];
+    }
+    let steps = (HEAT_LEVELS.len() - 1) as u32;
+    let level = (value * steps).div_ceil(max).clamp(1, steps);
+    HEAT_LEVELS[level as usize]
+}
+
 const fn convert_color_type(color: catppuccin::Color) -> Color {
     return Color::Rgb(color.rgb.r, color.rgb.g, color.rgb.b);
 }
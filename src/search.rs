@@ -0,0 +1,101 @@
+use time::Date;
+
+/// A completion whose notes matched a full-text search.
+#[derive(Debug, Clone)]
+pub struct NoteMatch {
+    pub habit_id: u64,
+    pub habit_name: String,
+    pub date: Date,
+    pub notes: String,
+}
+
+/// Scores `candidate` against `query` when every query character appears in it
+/// in order, ignoring case. Consecutive characters and matches at the start of
+/// a word score higher; `None` means no match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = next + candidate[next..].iter().position(|c| *c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    // shorter names win ties, so "run" prefers "running" over "morning run club"
+    Some(score * 100 - candidate.len() as i64)
+}
+
+/// Index of the best fuzzy match for `query` among `names`.
+pub fn best_match<'a>(query: &str, names: impl Iterator<Item = &'a str>) -> Option<usize> {
+    names
+        .enumerate()
+        .filter_map(|(i, name)| Some((i, fuzzy_score(query, name)?)))
+        .max_by_key(|(i, score)| (*score, std::cmp::Reverse(*i)))
+        .map(|(i, _)| i)
+}
+
+/// Turns free text into an FTS5 query: each word is matched as a quoted prefix,
+/// so punctuation in the search box can't break the query syntax.
+pub fn fts_query(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_query_character_must_appear_in_order() {
+        assert!(fuzzy_score("rn", "Running").is_some());
+        assert!(fuzzy_score("RUN", "morning run").is_some());
+        assert_eq!(fuzzy_score("nur", "Running"), None);
+        assert_eq!(fuzzy_score("swim", "Running"), None);
+    }
+
+    #[test]
+    fn spaces_in_the_query_are_ignored() {
+        assert_eq!(fuzzy_score("re ad", "Read"), fuzzy_score("read", "Read"));
+    }
+
+    #[test]
+    fn consecutive_and_word_start_matches_score_higher() {
+        let score = |query| fuzzy_score(query, "read books").unwrap();
+        assert!(score("read") > score("rdbk"));
+        assert!(score("bo") > score("oo"));
+    }
+
+    #[test]
+    fn best_match_prefers_the_closer_name() {
+        let names = ["morning run club", "running", "drink water"];
+        assert_eq!(best_match("run", names.into_iter()), Some(1));
+        assert_eq!(best_match("water", names.into_iter()), Some(2));
+        assert_eq!(best_match("yoga", names.into_iter()), None);
+    }
+
+    #[test]
+    fn best_match_keeps_the_first_of_equal_names() {
+        let names = ["Read", "read"];
+        assert_eq!(best_match("read", names.into_iter()), Some(0));
+    }
+
+    #[test]
+    fn fts_query_quotes_each_word_as_a_prefix() {
+        assert_eq!(fts_query("  long run "), r#""long"* "run"*"#);
+        assert_eq!(
+            fts_query(r#"said "hi" -x OR"#),
+            r#""said"* """hi"""* "-x"* "OR"*"#
+        );
+        assert_eq!(fts_query(""), "");
+    }
+}
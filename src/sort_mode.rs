@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// Order of the habit list.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum SortMode {
+    /// The saved order, changed with J/K.
+    #[default]
+    Manual,
+    Name,
+    /// Longest current streak first.
+    Streak,
+    /// Most hours this week first.
+    WeekHours,
+    /// Habits due today first, done ones last.
+    Due,
+}
+
+impl SortMode {
+    pub fn next(&mut self) {
+        *self = match self {
+            SortMode::Manual => SortMode::Name,
+            SortMode::Name => SortMode::Streak,
+            SortMode::Streak => SortMode::WeekHours,
+            SortMode::WeekHours => SortMode::Due,
+            SortMode::Due => SortMode::Manual,
+        }
+    }
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SortMode::Manual => write!(f, "manual"),
+            SortMode::Name => write!(f, "name"),
+            SortMode::Streak => write!(f, "streak"),
+            SortMode::WeekHours => write!(f, "hours this week"),
+            SortMode::Due => write!(f, "due today"),
+        }
+    }
+}
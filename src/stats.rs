@@ -0,0 +1,164 @@
+use time::{Date, Duration, Weekday};
+
+use crate::streak;
+
+/// Windows, in days, the completion rate is reported over.
+pub const RATE_WINDOWS: [i64; 3] = [30, 90, 365];
+
+/// Consistency statistics for one habit, computed from its daily totals.
+#[derive(Debug, Clone)]
+pub struct HabitStats {
+    /// Completion rate against the schedule for each of [`RATE_WINDOWS`], from 0.0 to 1.0.
+    pub completion_rates: [f64; 3],
+    pub best_weekday: Option<Weekday>,
+    pub worst_weekday: Option<Weekday>,
+    /// Most days between two completions, counting the gap up to today.
+    pub longest_gap: i64,
+    /// Mean hours of the entries that logged hours, so each of a counter
+    /// habit's sessions counts on its own.
+    pub average_session_hours: f64,
+    /// Days that reached the daily target.
+    pub total_completions: u32,
+    /// Everything logged, partial days of counter habits included.
+    pub total_entries: u32,
+    pub first_completed: Option<Date>,
+}
+
+impl HabitStats {
+    /// `daily` holds `(date, hours, entries)` rows sorted by date and `sessions`
+    /// is the number of entries that logged hours; `frequency` is the number of
+    /// days allowed between completions. Only days with `daily_target` entries
+    /// count as completed, as for streaks.
+    pub fn compute(
+        daily: &[(Date, f64, u32)],
+        sessions: u32,
+        frequency: u32,
+        daily_target: u32,
+        today: Date,
+    ) -> Self {
+        let full_days: Vec<Date> = daily
+            .iter()
+            .filter(|(_, _, entries)| *entries >= daily_target.max(1))
+            .map(|(date, _, _)| *date)
+            .collect();
+        let first_completed = full_days.first().copied();
+        let completion_rates = RATE_WINDOWS
+            .map(|window| completion_rate(&full_days, frequency, today, window, first_completed));
+
+        let mut per_weekday = [0u32; 7];
+        for date in &full_days {
+            per_weekday[date.weekday().number_days_from_monday() as usize] += 1;
+        }
+        let (best_weekday, worst_weekday) = if full_days.is_empty() {
+            (None, None)
+        } else {
+            let weekday = |i: usize| Weekday::Monday.nth_next(i as u8);
+            // ties go to the earlier day in both directions
+            let best = (0..7).rev().max_by_key(|i| per_weekday[*i]).map(weekday);
+            let worst = (0..7).min_by_key(|i| per_weekday[*i]).map(weekday);
+            (best, worst)
+        };
+
+        let mut longest_gap = 0;
+        let mut previous: Option<Date> = None;
+        for date in &full_days {
+            if let Some(previous) = previous {
+                longest_gap = longest_gap.max((*date - previous).whole_days());
+            }
+            previous = Some(*date);
+        }
+        if let Some(last) = previous {
+            longest_gap = longest_gap.max((today - last).whole_days());
+        }
+
+        let average_session_hours = if sessions == 0 {
+            0.0
+        } else {
+            daily.iter().map(|(_, hours, _)| hours).sum::<f64>() / f64::from(sessions)
+        };
+
+        HabitStats {
+            completion_rates,
+            best_weekday,
+            worst_weekday,
+            longest_gap,
+            average_session_hours,
+            total_completions: full_days.len() as u32,
+            total_entries: daily.iter().map(|(_, _, entries)| entries).sum(),
+            first_completed,
+        }
+    }
+}
+
+/// Figures for a quit habit, where a good day is one without a slip.
+#[derive(Debug, Clone)]
+pub struct QuitStats {
+    /// Share of days without a slip for each of [`RATE_WINDOWS`], from 0.0 to 1.0.
+    pub clean_rates: [f64; 3],
+    pub slips: u32,
+    /// Weekday with the most slips.
+    pub worst_weekday: Option<Weekday>,
+    /// Most days in a row without a slip, counting the run up to today.
+    pub longest_clean: u32,
+    pub last_slip: Option<Date>,
+}
+
+impl QuitStats {
+    /// `slips` are sorted and none are before `since`, the day the habit became a
+    /// quit habit.
+    pub fn compute(slips: &[Date], since: Date, today: Date) -> Self {
+        let slips: Vec<Date> = slips
+            .iter()
+            .copied()
+            .filter(|date| *date <= today)
+            .collect();
+        let clean_rates = RATE_WINDOWS.map(|window| {
+            let start = (today - Duration::days(window - 1)).max(since);
+            let days = (today - start).whole_days() + 1;
+            if days <= 0 {
+                return 0.0;
+            }
+            let slipped = slips.iter().filter(|date| **date >= start).count() as i64;
+            (days - slipped) as f64 / days as f64
+        });
+
+        let mut per_weekday = [0u32; 7];
+        for date in &slips {
+            per_weekday[date.weekday().number_days_from_monday() as usize] += 1;
+        }
+        let worst_weekday = (!slips.is_empty())
+            .then(|| (0..7).rev().max_by_key(|i| per_weekday[*i]))
+            .flatten()
+            .map(|i| Weekday::Monday.nth_next(i as u8));
+
+        QuitStats {
+            clean_rates,
+            slips: slips.len() as u32,
+            worst_weekday,
+            longest_clean: streak::longest_clean(&slips, since, today),
+            last_slip: slips.last().copied(),
+        }
+    }
+}
+
+/// Days completed in the last `window` days against the number the schedule asks for.
+/// The window never reaches back before the first completion.
+fn completion_rate(
+    full_days: &[Date],
+    frequency: u32,
+    today: Date,
+    window: i64,
+    first_completed: Option<Date>,
+) -> f64 {
+    let Some(first) = first_completed else {
+        return 0.0;
+    };
+    let start = (today - Duration::days(window - 1)).max(first);
+    let days = (today - start).whole_days() + 1;
+    let expected = (days as f64 / f64::from(frequency.max(1))).ceil().max(1.0);
+    let done = full_days
+        .iter()
+        .filter(|date| **date >= start && **date <= today)
+        .count();
+    (done as f64 / expected).min(1.0)
+}
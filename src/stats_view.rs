@@ -0,0 +1,43 @@
+/// Which chart the stats panel is showing.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum StatsView {
+    #[default]
+    History,
+    Trend,
+    Summary,
+    /// Totals across habits for each tag and category.
+    Tags,
+}
+
+impl StatsView {
+    pub fn next(&mut self) {
+        *self = match self {
+            StatsView::History => StatsView::Trend,
+            StatsView::Trend => StatsView::Summary,
+            StatsView::Summary => StatsView::Tags,
+            StatsView::Tags => StatsView::History,
+        }
+    }
+}
+
+/// What the stats charts plot.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum ChartValue {
+    #[default]
+    Hours,
+    Completions,
+    /// Index into the selected habit's metrics.
+    Metric(usize),
+}
+
+impl ChartValue {
+    /// Cycles hours, completions, then each of the habit's `metric_count` metrics.
+    pub fn next(&mut self, metric_count: usize) {
+        *self = match self {
+            ChartValue::Hours => ChartValue::Completions,
+            ChartValue::Completions if metric_count > 0 => ChartValue::Metric(0),
+            ChartValue::Metric(i) if *i + 1 < metric_count => ChartValue::Metric(*i + 1),
+            _ => ChartValue::Hours,
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+
+use time::{Date, Duration, Month};
+
+/// The completions making up the current streak, the missed days a streak
+/// freeze was spent on to keep it going, and the longest streak on record.
+#[derive(Debug, Default, Clone)]
+pub struct StreakWalk {
+    pub dates: Vec<Date>,
+    pub frozen: Vec<Date>,
+    pub longest: u32,
+}
+
+impl StreakWalk {
+    pub fn current(&self) -> u32 {
+        self.dates.len() as u32
+    }
+}
+
+/// Walks back from `today` through `completed` (newest first) while each completion
+/// is at most `frequency` days before the next one's window, then on through the
+/// older runs to find the longest streak.
+///
+/// `excused` days (skips and vacations) keep the streak going without counting
+/// towards it. When a gap is still too long, up to `monthly_freezes` missed days
+/// per calendar month are frozen instead of breaking the streak; the allowance
+/// is shared by every run, so an older streak cannot reuse a month's freezes.
+pub fn walk(
+    completed: &[Date],
+    excused: &HashSet<Date>,
+    frequency: u32,
+    monthly_freezes: u32,
+    today: Date,
+) -> StreakWalk {
+    let completed_set: HashSet<&Date> = completed.iter().collect();
+    let mut entries: Vec<(Date, bool)> = completed
+        .iter()
+        .filter(|date| **date <= today)
+        .map(|date| (*date, true))
+        .collect();
+    entries.extend(
+        excused
+            .iter()
+            .filter(|date| **date <= today && !completed_set.contains(date))
+            .map(|date| (*date, false)),
+    );
+    entries.sort_by_key(|(date, _)| std::cmp::Reverse(*date));
+
+    let mut used: HashMap<(i32, Month), u32> = HashMap::new();
+    let (mut walk, mut next) = run(&entries, today, frequency, monthly_freezes, &mut used);
+    walk.longest = walk.current();
+    while next < entries.len() {
+        match entries[next..]
+            .iter()
+            .position(|(_, is_completion)| *is_completion)
+        {
+            Some(offset) => next += offset,
+            None => break,
+        }
+        let (older, end) = run(
+            &entries[next..],
+            entries[next].0,
+            frequency,
+            monthly_freezes,
+            &mut used,
+        );
+        walk.longest = walk.longest.max(older.current());
+        next += end;
+    }
+    walk
+}
+
+/// One streak through `entries` (newest first) ending at `start`, and the index
+/// of the entry that broke it. Freezes spent are added to `used`.
+fn run(
+    entries: &[(Date, bool)],
+    start: Date,
+    frequency: u32,
+    monthly_freezes: u32,
+    used: &mut HashMap<(i32, Month), u32>,
+) -> (StreakWalk, usize) {
+    let frequency = i64::from(frequency);
+    let mut walk = StreakWalk::default();
+    let mut expected = start;
+    // freezes only count once a completion further back shows the streak carried on
+    let mut pending_frozen: Vec<Date> = Vec::new();
+    for (index, (date, is_completion)) in entries.iter().copied().enumerate() {
+        if date <= expected {
+            let missed = (expected - date).whole_days() - frequency;
+            if missed > 0 {
+                let days: Vec<Date> = (1..=missed).map(|d| date + Duration::days(d)).collect();
+                let mut needed: HashMap<(i32, Month), u32> = HashMap::new();
+                for day in &days {
+                    *needed.entry((day.year(), day.month())).or_default() += 1;
+                }
+                let affordable = needed.iter().all(|(month, count)| {
+                    used.get(month).copied().unwrap_or(0) + count <= monthly_freezes
+                });
+                if !affordable {
+                    refund(used, &pending_frozen);
+                    return (walk, index);
+                }
+                for (month, count) in needed {
+                    *used.entry(month).or_default() += count;
+                }
+                pending_frozen.extend(days);
+            }
+            expected = date - Duration::days(frequency);
+        }
+        if is_completion {
+            walk.dates.push(date);
+            walk.frozen.append(&mut pending_frozen);
+        }
+    }
+    refund(used, &pending_frozen);
+    (walk, entries.len())
+}
+
+/// Gives back freezes spent on days no completion confirmed.
+fn refund(used: &mut HashMap<(i32, Month), u32>, days: &[Date]) {
+    for day in days {
+        if let Some(count) = used.get_mut(&(day.year(), day.month())) {
+            *count -= 1;
+        }
+    }
+}
+
+/// Whole days since the last slip up to today, or since `since` (when the habit
+/// became a quit habit) without one. `slips` are sorted.
+pub fn clean_streak(slips: &[Date], since: Date, today: Date) -> u32 {
+    let last = slips.iter().rev().find(|date| **date <= today).copied();
+    let from = last.map_or(since, |last| last.max(since));
+    (today - from).whole_days().max(0) as u32
+}
+
+/// Most days in a row without a slip, from `since` (when the habit became a quit
+/// habit) up to today, counted like the clean streak: the days after `since` or
+/// a slip. `slips` are sorted.
+pub fn longest_clean(slips: &[Date], since: Date, today: Date) -> u32 {
+    let mut longest = 0;
+    let mut previous = since;
+    for date in slips
+        .iter()
+        .filter(|date| **date >= since && **date <= today)
+    {
+        longest = longest.max((*date - previous).whole_days() - 1);
+        previous = *date;
+    }
+    longest.max((today - previous).whole_days()).max(0) as u32
+}
+
+/// Every day from `start` to `end`, inclusive.
+pub fn days_between(start: Date, end: Date) -> Vec<Date> {
+    (0..=(end - start).whole_days())
+        .map(|d| start + Duration::days(d))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> Date {
+        Date::from_calendar_date(2026, Month::March, 31).unwrap()
+    }
+
+    /// `n` days before [`today`].
+    fn ago(n: i64) -> Date {
+        today() - Duration::days(n)
+    }
+
+    fn days(ns: &[i64]) -> Vec<Date> {
+        ns.iter().map(|n| ago(*n)).collect()
+    }
+
+    fn daily(completed: &[i64], excused: &[i64], monthly_freezes: u32) -> StreakWalk {
+        let excused: HashSet<Date> = days(excused).into_iter().collect();
+        walk(&days(completed), &excused, 1, monthly_freezes, today())
+    }
+
+    #[test]
+    fn no_completions_is_no_streak() {
+        let walk = daily(&[], &[], 0);
+        assert_eq!(walk.current(), 0);
+        assert_eq!(walk.longest, 0);
+    }
+
+    #[test]
+    fn a_gap_ends_the_streak() {
+        let walk = daily(&[0, 1, 2, 5, 6], &[], 0);
+        assert_eq!(walk.dates, days(&[0, 1, 2]));
+        assert_eq!(walk.longest, 3);
+    }
+
+    #[test]
+    fn today_may_still_be_done() {
+        assert_eq!(daily(&[1, 2], &[], 0).current(), 2);
+    }
+
+    #[test]
+    fn a_lapsed_streak_still_counts_as_longest() {
+        let walk = daily(&[3, 4], &[], 0);
+        assert_eq!(walk.current(), 0);
+        assert_eq!(walk.longest, 2);
+    }
+
+    #[test]
+    fn longest_is_found_in_older_runs() {
+        let walk = daily(&[0, 1, 10, 11, 12, 13, 14], &[], 0);
+        assert_eq!(walk.current(), 2);
+        assert_eq!(walk.longest, 5);
+    }
+
+    #[test]
+    fn future_dates_are_ignored() {
+        let excused: HashSet<Date> = HashSet::new();
+        let tomorrow = today() + Duration::days(1);
+        let walk = walk(&[tomorrow, today()], &excused, 1, 0, today());
+        assert_eq!(walk.dates, vec![today()]);
+        assert_eq!(walk.longest, 1);
+    }
+
+    #[test]
+    fn skipped_days_bridge_a_gap_without_counting() {
+        let walk = daily(&[0, 3], &[2], 0);
+        assert_eq!(walk.dates, days(&[0, 3]));
+        assert!(walk.frozen.is_empty());
+    }
+
+    #[test]
+    fn vacations_bridge_long_gaps() {
+        let vacation: Vec<i64> = (1..10).collect();
+        assert_eq!(daily(&[0, 10], &vacation, 0).current(), 2);
+    }
+
+    #[test]
+    fn freezes_cover_missed_days_within_the_budget() {
+        let walk = daily(&[0, 4], &[], 2);
+        assert_eq!(walk.dates, days(&[0, 4]));
+        assert_eq!(walk.frozen, days(&[3, 2]));
+        assert_eq!(daily(&[0, 4], &[], 1).current(), 1);
+    }
+
+    #[test]
+    fn the_budget_is_per_calendar_month() {
+        // the missed days straddle February and March, one freeze in each
+        let feb_28 = Date::from_calendar_date(2026, Month::February, 28).unwrap();
+        let mar_3 = Date::from_calendar_date(2026, Month::March, 3).unwrap();
+        let walk = walk(
+            &[mar_3, feb_28 - Duration::days(1)],
+            &HashSet::new(),
+            1,
+            1,
+            mar_3,
+        );
+        assert_eq!(walk.current(), 2);
+        assert_eq!(walk.frozen.len(), 2);
+    }
+
+    #[test]
+    fn freezes_need_a_completion_further_back() {
+        let walk = daily(&[0], &[3], 5);
+        assert_eq!(walk.current(), 1);
+        assert!(walk.frozen.is_empty());
+    }
+
+    #[test]
+    fn older_runs_share_the_monthly_budget() {
+        // the current run spends March's only freeze, so the older run breaks at
+        // its own gap instead of counting 10, 13, 14, 15
+        let walk = daily(&[0, 3, 10, 13, 14, 15], &[], 1);
+        assert_eq!(walk.current(), 2);
+        assert_eq!(walk.longest, 3);
+    }
+
+    #[test]
+    fn unconfirmed_freezes_are_given_back() {
+        // the freeze before the skip on day 3 is never confirmed, so the older
+        // run may still spend it
+        let walk = daily(&[0, 10, 13, 14], &[3], 1);
+        assert_eq!(walk.current(), 1);
+        assert_eq!(walk.longest, 3);
+    }
+
+    #[test]
+    fn clean_streak_counts_from_the_last_slip() {
+        assert_eq!(clean_streak(&days(&[7, 3]), ago(10), today()), 3);
+        assert_eq!(clean_streak(&[], ago(10), today()), 10);
+        assert_eq!(clean_streak(&days(&[0]), ago(10), today()), 0);
+        // slips from before quitting and after today do not count
+        assert_eq!(clean_streak(&days(&[20]), ago(10), today()), 10);
+        let tomorrow = today() + Duration::days(1);
+        assert_eq!(clean_streak(&[ago(4), tomorrow], ago(10), today()), 4);
+    }
+
+    #[test]
+    fn longest_clean_counts_runs_between_slips() {
+        assert_eq!(longest_clean(&days(&[7, 3]), ago(10), today()), 3);
+        assert_eq!(longest_clean(&[], ago(10), today()), 10);
+        // slips from before quitting are not counted
+        assert_eq!(longest_clean(&days(&[20]), ago(10), today()), 10);
+    }
+}
@@ -3,6 +3,7 @@ use crossterm::event::KeyCode;
 #[derive(Default, Debug)]
 pub struct TextInput {
     pub content: String,
+    /// Byte offset into `content`, always on a character boundary.
     pub cursor_position: usize,
 }
 
@@ -11,11 +12,11 @@ impl TextInput {
         match key {
             KeyCode::Char(c) => {
                 self.content.insert(self.cursor_position, c);
-                self.cursor_position += 1;
+                self.cursor_position += c.len_utf8();
             }
             KeyCode::Backspace => {
-                if self.cursor_position > 0 {
-                    self.cursor_position -= 1;
+                if let Some(c) = self.content[..self.cursor_position].chars().next_back() {
+                    self.cursor_position -= c.len_utf8();
                     self.content.remove(self.cursor_position);
                 }
             }
@@ -25,13 +26,13 @@ impl TextInput {
                 }
             }
             KeyCode::Left => {
-                if self.cursor_position > 0 {
-                    self.cursor_position -= 1;
+                if let Some(c) = self.content[..self.cursor_position].chars().next_back() {
+                    self.cursor_position -= c.len_utf8();
                 }
             }
             KeyCode::Right => {
-                if self.cursor_position < self.content.len() {
-                    self.cursor_position += 1;
+                if let Some(c) = self.content[self.cursor_position..].chars().next() {
+                    self.cursor_position += c.len_utf8();
                 }
             }
             KeyCode::Esc | KeyCode::BackTab => {
@@ -45,3 +46,60 @@ impl TextInput {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(text: &str) -> TextInput {
+        let mut input = TextInput::default();
+        for c in text.chars() {
+            input.handle_key(KeyCode::Char(c));
+        }
+        input
+    }
+
+    #[test]
+    fn types_multi_byte_characters() {
+        let input = typed("café! 🏃");
+        assert_eq!(input.content, "café! 🏃");
+        assert_eq!(input.cursor_position, input.content.len());
+    }
+
+    #[test]
+    fn backspace_removes_whole_characters() {
+        let mut input = typed("naïve");
+        input.handle_key(KeyCode::Backspace);
+        input.handle_key(KeyCode::Backspace);
+        input.handle_key(KeyCode::Backspace);
+        assert_eq!(input.content, "na");
+        input.handle_key(KeyCode::Backspace);
+        input.handle_key(KeyCode::Backspace);
+        input.handle_key(KeyCode::Backspace);
+        assert_eq!(input.content, "");
+    }
+
+    #[test]
+    fn edits_in_the_middle_of_multi_byte_text() {
+        let mut input = typed("cafe");
+        input.handle_key(KeyCode::Left);
+        input.handle_key(KeyCode::Backspace);
+        input.handle_key(KeyCode::Char('f'));
+        input.handle_key(KeyCode::Char('é'));
+        input.handle_key(KeyCode::Left);
+        input.handle_key(KeyCode::Left);
+        input.handle_key(KeyCode::Delete);
+        input.handle_key(KeyCode::Right);
+        input.handle_key(KeyCode::Char('è'));
+        assert_eq!(input.content, "caéèe");
+        input.handle_key(KeyCode::Home);
+        input.handle_key(KeyCode::Right);
+        input.handle_key(KeyCode::Delete);
+        input.handle_key(KeyCode::Delete);
+        assert_eq!(input.content, "cèe");
+        input.handle_key(KeyCode::End);
+        input.handle_key(KeyCode::Right);
+        input.handle_key(KeyCode::Char('s'));
+        assert_eq!(input.content, "cèes");
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::user_habits::HabitItem;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimerKind {
+    Stopwatch,
+    /// Alternating work and break periods, in minutes; only work time is logged.
+    Pomodoro {
+        work: u32,
+        rest: u32,
+    },
+}
+
+impl TimerKind {
+    /// Reads `work break` minutes, e.g. "25 5".
+    pub fn parse_pomodoro(content: &str) -> Option<Self> {
+        let mut parts = content.split_whitespace().map(|part| part.parse::<u32>());
+        let work = parts.next()?.ok().filter(|work| *work > 0)?;
+        let rest = parts.next().unwrap_or(Ok(5)).ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(TimerKind::Pomodoro { work, rest })
+    }
+}
+
+/// A session being timed for one habit.
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub habit: HabitItem,
+    pub kind: TimerKind,
+    pub started: Instant,
+}
+
+impl Timer {
+    pub fn start(habit: HabitItem, kind: TimerKind) -> Self {
+        Timer {
+            habit,
+            kind,
+            started: Instant::now(),
+        }
+    }
+
+    /// Time that counts towards the habit: everything for a stopwatch, the work
+    /// periods of a pomodoro.
+    pub fn worked(&self, now: Instant) -> Duration {
+        let elapsed = now.duration_since(self.started);
+        match self.kind {
+            TimerKind::Stopwatch => elapsed,
+            TimerKind::Pomodoro { work, rest } => {
+                let work = Duration::from_secs(u64::from(work) * 60);
+                let cycle = work + Duration::from_secs(u64::from(rest) * 60);
+                let cycles = (elapsed.as_secs() / cycle.as_secs()) as u32;
+                let into = elapsed - cycle * cycles;
+                work * cycles + into.min(work)
+            }
+        }
+    }
+
+    /// Whether a pomodoro is on a break, and how long until the current period ends.
+    pub fn period(&self, now: Instant) -> Option<(bool, Duration)> {
+        let TimerKind::Pomodoro { work, rest } = self.kind else {
+            return None;
+        };
+        let work = Duration::from_secs(u64::from(work) * 60);
+        let cycle = work + Duration::from_secs(u64::from(rest) * 60);
+        let elapsed = now.duration_since(self.started);
+        let into = Duration::from_secs(elapsed.as_secs() % cycle.as_secs());
+        Some(if into < work {
+            (false, work - into)
+        } else {
+            (true, cycle - into)
+        })
+    }
+
+    /// Line shown under the habit list while the timer runs.
+    pub fn display(&self, now: Instant) -> String {
+        match self.period(now) {
+            None => format!("⏱ {} {}", self.habit.name, Clock(self.worked(now))),
+            Some((on_break, left)) => format!(
+                "🍅 {} {} {} left, {} worked",
+                self.habit.name,
+                if on_break { "break" } else { "work" },
+                Clock(left),
+                Clock(self.worked(now)),
+            ),
+        }
+    }
+}
+
+/// A duration as `m:ss`, or `h:mm:ss` from an hour up.
+pub struct Clock(pub Duration);
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs();
+        if secs >= 3600 {
+            write!(f, "{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+        } else {
+            write!(f, "{}:{:02}", secs / 60, secs % 60)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(m: u64) -> Duration {
+        Duration::from_secs(m * 60)
+    }
+
+    fn timer(kind: TimerKind) -> Timer {
+        Timer::start(HabitItem::default(), kind)
+    }
+
+    const POMODORO: TimerKind = TimerKind::Pomodoro { work: 25, rest: 5 };
+
+    #[test]
+    fn a_stopwatch_counts_everything() {
+        let timer = timer(TimerKind::Stopwatch);
+        assert_eq!(timer.worked(timer.started + minutes(42)), minutes(42));
+        assert_eq!(timer.period(timer.started + minutes(42)), None);
+    }
+
+    #[test]
+    fn a_pomodoro_counts_work_across_cycles() {
+        let timer = timer(POMODORO);
+        let at = |m| timer.started + minutes(m);
+        assert_eq!(timer.worked(at(10)), minutes(10));
+        assert_eq!(timer.period(at(10)), Some((false, minutes(15))));
+        assert_eq!(timer.worked(at(65)), minutes(55));
+        assert_eq!(timer.period(at(65)), Some((false, minutes(20))));
+        assert_eq!(timer.worked(at(90)), minutes(75));
+        assert_eq!(timer.period(at(90)), Some((false, minutes(25))));
+    }
+
+    #[test]
+    fn stopping_mid_break_logs_only_the_work() {
+        let timer = timer(POMODORO);
+        let at = |m| timer.started + minutes(m);
+        assert_eq!(timer.worked(at(27)), minutes(25));
+        assert_eq!(timer.period(at(27)), Some((true, minutes(3))));
+        assert_eq!(timer.worked(at(87)), minutes(75));
+        assert_eq!(timer.period(at(87)), Some((true, minutes(3))));
+    }
+
+    #[test]
+    fn no_break_is_all_work() {
+        let timer = timer(TimerKind::Pomodoro { work: 25, rest: 0 });
+        let at = |m| timer.started + minutes(m);
+        assert_eq!(timer.worked(at(60)), minutes(60));
+        assert_eq!(timer.period(at(60)), Some((false, minutes(15))));
+    }
+
+    #[test]
+    fn parses_work_and_break_minutes() {
+        assert_eq!(
+            TimerKind::parse_pomodoro("50 10"),
+            Some(TimerKind::Pomodoro { work: 50, rest: 10 })
+        );
+        assert_eq!(TimerKind::parse_pomodoro("25"), Some(POMODORO));
+        assert_eq!(
+            TimerKind::parse_pomodoro("25 0"),
+            Some(TimerKind::Pomodoro { work: 25, rest: 0 })
+        );
+        for content in ["", "0 5", "25 -5", "25 5 5", "half"] {
+            assert_eq!(TimerKind::parse_pomodoro(content), None, "{content:?}");
+        }
+    }
+
+    #[test]
+    fn clock_shows_hours_only_when_needed() {
+        assert_eq!(Clock(Duration::from_secs(65)).to_string(), "1:05");
+        assert_eq!(Clock(Duration::from_secs(3725)).to_string(), "1:02:05");
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+
+/// Window of the short moving average, in days.
+pub const SHORT_WINDOW: usize = 7;
+/// Window of the long moving average, in days.
+pub const LONG_WINDOW: usize = 30;
+
+/// Trailing mean of the values present in each `window`; days without a value
+/// (`None`) are left out rather than counted as zero.
+pub fn moving_average(values: &[Option<f64>], window: usize) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let start = (i + 1).saturating_sub(window);
+            let present: Vec<f64> = values[start..=i].iter().flatten().copied().collect();
+            if present.is_empty() {
+                0.0
+            } else {
+                present.iter().sum::<f64>() / present.len() as f64
+            }
+        })
+        .collect()
+}
+
+/// Whether recent effort is above or below the longer-term average.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Trend {
+    Increasing,
+    Steady,
+    Slipping,
+}
+
+impl Trend {
+    /// Compares the latest short and long moving averages; within 10% counts as steady.
+    pub fn from_averages(short: f64, long: f64) -> Self {
+        if long == 0.0 {
+            return if short > 0.0 {
+                Trend::Increasing
+            } else {
+                Trend::Steady
+            };
+        }
+        let ratio = short / long;
+        if ratio > 1.1 {
+            Trend::Increasing
+        } else if ratio < 0.9 {
+            Trend::Slipping
+        } else {
+            Trend::Steady
+        }
+    }
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Trend::Increasing => write!(f, "▲ increasing"),
+            Trend::Steady => write!(f, "► steady"),
+            Trend::Slipping => write!(f, "▼ slipping"),
+        }
+    }
+}
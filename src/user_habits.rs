@@ -1,6 +1,13 @@
 use ratatui::{
     widgets::{ListState},
 };
+
+use crate::calendar_view::CalendarView;
+use crate::challenge::{Challenge, Outcome};
+use crate::db::TimeFrame;
+use crate::habit_table::HabitColumn;
+use crate::sort_mode::SortMode;
+use crate::stats_view::StatsView;
 // struct that saves habit into database schema
 #[derive(Debug, Default, Clone)]
 pub struct HabitItem {
@@ -10,15 +17,72 @@ pub struct HabitItem {
     pub active: bool, 
     pub frequency: u32,
     pub current_streak: u32,
-    pub max_streak: u32
+    pub max_streak: u32,
+    /// Optional hour targets; the stats chart scales to these when set.
+    pub weekly_target: Option<f32>,
+    pub monthly_target: Option<f32>,
+    pub yearly_target: Option<f32>,
+    /// Entries needed for a day to count as done; above 1 makes this a counter habit.
+    pub daily_target: u32,
+    /// Quit habits track slips: success is a day with nothing logged.
+    pub quit: bool,
+    /// Date the habit was switched to a quit habit; clean days are counted from here.
+    pub quit_since: Option<String>,
+    /// Missed days per month that may be frozen instead of breaking the streak.
+    pub monthly_freezes: u32,
+    pub challenge: Option<Challenge>,
+    /// How the challenge ended; set when it is archived.
+    pub challenge_result: Option<Outcome>,
+    /// Section the habit is listed under, e.g. health or work.
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl HabitItem {
+    /// Whether `label` is the habit's category or one of its tags.
+    pub fn has_label(&self, label: &str) -> bool {
+        self.category.as_deref() == Some(label) || self.tags.iter().any(|tag| tag == label)
+    }
+
+    pub fn is_counter(&self) -> bool {
+        self.daily_target > 1
+    }
+
+    pub fn target(&self, tf: TimeFrame) -> Option<f32> {
+        match tf {
+            TimeFrame::Week => self.weekly_target,
+            TimeFrame::Month => self.monthly_target,
+            TimeFrame::Year => self.yearly_target,
+        }
+    }
+}
+/// How a metric's values combine over a week, month or year.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Aggregate {
+    #[default]
+    Sum,
+    Average,
 }
+
+/// A user defined quantity logged alongside a habit, e.g. pages read or km run.
+#[derive(Debug, Default, Clone)]
+pub struct HabitMetric {
+    pub id: u64,
+    pub name: String,
+    pub unit: String,
+    pub aggregate: Aggregate,
+}
+
 #[derive(Debug, Default, Clone)]
 // struct that saves habit into calendar for db schema
 pub struct habit_calendar {
     pub id: u64,
     pub date_completed: String,
     pub hours: f32,
-    pub notes: String
+    pub notes: String,
+    pub entry_id: u64,
+    /// UTC "YYYY-MM-DD HH:MM:SS" the entry was logged, or just the date for old entries.
+    pub logged_at: String,
 }
 
 
@@ -27,8 +91,16 @@ pub struct habit_calendar {
 pub struct UserHabits {
     pub show_habit_list: bool,
     pub show_add_habit: bool,
+    pub show_dashboard: bool,
+    /// List archived habits (finished challenges) as well as active ones.
+    pub show_archived: bool,
+    pub sort_mode: SortMode,
+    /// Table columns turned off by the user.
+    pub hidden_columns: Vec<HabitColumn>,
     pub habit_calendar_track: bool,
     pub habit_stats: bool,
+    pub calendar_view: CalendarView,
+    pub stats_view: StatsView,
     pub items: Vec<HabitItem>,
     pub state: ListState,
 }